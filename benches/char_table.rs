@@ -0,0 +1,56 @@
+#![allow(missing_docs)]
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use komb::{
+	string::{char, char_table, CharTable, Error},
+	PResult, Parser,
+};
+
+const IDENT: &[char] = &[
+	'_', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c',
+	'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q',
+	'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+fn is_ident(ch: char) -> bool {
+	IDENT.contains(&ch)
+}
+
+fn run<'a>(
+	p: &impl Parser<'a, &'a str, &'a str, Error<'a>>,
+	input: &'a str,
+) -> usize {
+	let mut rest = input;
+	let mut count = 0;
+	while let Ok((_, r)) = p.parse(rest) {
+		rest = r;
+		count += 1;
+	}
+	count
+}
+
+fn bench<'a>(
+	name: &str,
+	p: impl Parser<'a, &'a str, &'a str, Error<'a>>,
+	input: &'a str,
+) {
+	let start = Instant::now();
+	for _ in 0..100 {
+		black_box(run(&p, black_box(input)));
+	}
+	let elapsed = start.elapsed();
+	println!("{name:>12}: {elapsed:?}");
+}
+
+fn main() {
+	let input = "some_identifier_with_digits_0123456789_".repeat(10_000);
+
+	fn predicate(input: &str) -> PResult<&str, &str, Error<'_>> {
+		char(is_ident).parse(input)
+	}
+
+	bench("char", predicate, &input);
+	bench("char_table", char_table(CharTable::new(is_ident)), &input);
+}
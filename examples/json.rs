@@ -4,10 +4,10 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use komb::{
-	combinator::{choice, delimited, fold, optional},
+	combinator::{choice, delimited, delimited_list, optional},
 	string::{
-		anycase, consume, digits1, eof, none_of_char, one_of0, take,
-		Error,
+		all_consuming, anycase, boolean, consume, escaped_transform,
+		none_of, one_of, or0, take, Error,
 	},
 	PResult, Parser,
 };
@@ -22,45 +22,43 @@ enum Value {
 	Object(HashMap<String, Value>),
 }
 
-fn whitespace(input: &str) -> PResult<&str, (), Error> {
-	one_of0(&[' ', '\n', '\r', '\t']).value(()).parse(input)
+fn whitespace(input: &str) -> PResult<&str, (), Error<'_>> {
+	or0(one_of(&[' ', '\n', '\r', '\t'])).value(()).parse(input)
 }
 
-fn string(input: &str) -> PResult<&str, String, Error> {
-	let u_esc = "\\u".and_then(take(4).map(|v| {
+fn string(input: &str) -> PResult<&str, String, Error<'_>> {
+	let u_esc = 'u'.and_then(take(4).map(|v| {
 		let s = v?;
 		let num = u32::from_str_radix(s, 16)
 			.map_err(|error| Error::ParseInt { error, span: s })?;
 		Ok(char::from_u32(num).unwrap())
 	}));
 
-	let character = choice((
-		"\\".value('\"'),
-		"\\\\".value('\\'),
-		"\\/".value('/'),
-		"\\b".value('\x08'),
-		"\\f".value('\x0C'),
-		"\\n".value('\n'),
-		"\\r".value('\r'),
-		"\\t".value('\t'),
+	let escape = choice((
+		'"'.value('"'),
+		'\\'.value('\\'),
+		'/'.value('/'),
+		'b'.value('\x08'),
+		'f'.value('\x0C'),
+		'n'.value('\n'),
+		'r'.value('\r'),
+		't'.value('\t'),
 		u_esc,
-		none_of_char(&['\\', '"'])
-			.map_out(|s| s.chars().next().unwrap()),
 	));
 
-	let p = fold(character, String::new(), |acc, ch| acc.push(ch));
+	let p = escaped_transform(none_of(&['\\', '"']), '\\', escape);
 
-	delimited("\"", p, "\"").coerce().parse(input)
+	delimited("\"", p, "\"").parse(input)
 }
 
-fn number(input: &str) -> PResult<&str, f64, Error> {
-	fn digits(input: &str) -> PResult<&str, &str, Error> {
-		let (out, rest) = digits1::<10>.parse(input)?;
+fn number(input: &str) -> PResult<&str, f64, Error<'_>> {
+	fn digits(input: &str) -> PResult<&str, &str, Error<'_>> {
+		let (out, rest) = komb::string::digits::<10>.parse(input)?;
 
 		Ok((out, rest))
 	}
 
-	fn digits_leading(input: &str) -> PResult<&str, &str, Error> {
+	fn digits_leading(input: &str) -> PResult<&str, &str, Error<'_>> {
 		let (out, rest) = digits.parse(input)?;
 
 		// multi-character digits cannot start with a zero
@@ -88,38 +86,20 @@ fn number(input: &str) -> PResult<&str, f64, Error> {
 	Ok((number, rest))
 }
 
-fn object(input: &str) -> PResult<&str, HashMap<String, Value>, Error> {
-	let pair = (
-		whitespace,
-		string,
-		whitespace,
-		":",
-		value,
-		optional(","),
-		whitespace,
-	)
-		.map_out(|tuple| (tuple.1, tuple.4));
+fn object(input: &str) -> PResult<&str, HashMap<String, Value>, Error<'_>> {
+	let pair = (whitespace, string, whitespace, ':', value)
+		.map_out(|(_, key, _, _, value)| (key, value));
 
-	let folded = fold(pair, HashMap::new(), |acc, (k, v)| {
-		acc.insert(k, v);
-	});
-
-	delimited("{".before(whitespace), folded, "}").parse(input)
+	delimited_list(('{', whitespace), pair, ',', '}')
+		.map_out(HashMap::from_iter)
+		.parse(input)
 }
 
-fn array(input: &str) -> PResult<&str, Vec<Value>, Error> {
-	let folded = fold(value.before(','), Vec::new(), |acc, value| {
-		acc.push(value)
-	});
-	let elements = (folded, value).map_out(|(mut arr, last)| {
-		arr.push(last);
-		arr
-	});
-
-	delimited("[", elements, "]").parse(input)
+fn array(input: &str) -> PResult<&str, Vec<Value>, Error<'_>> {
+	delimited_list(('[', whitespace), value, ',', ']').parse(input)
 }
 
-fn value(input: &str) -> PResult<&str, Value, Error> {
+fn value(input: &str) -> PResult<&str, Value, Error<'_>> {
 	delimited(
 		whitespace,
 		choice((
@@ -127,8 +107,7 @@ fn value(input: &str) -> PResult<&str, Value, Error> {
 			array.map_out(Value::Array),
 			string.map_out(Value::String),
 			number.map_out(Value::Number),
-			"true".value(Value::Bool(true)),
-			"false".value(Value::Bool(false)),
+			boolean.map_out(Value::Bool),
 			"null".value(Value::Null),
 		)),
 		whitespace,
@@ -136,8 +115,8 @@ fn value(input: &str) -> PResult<&str, Value, Error> {
 	.parse(input)
 }
 
-fn parse(input: &str) -> Result<Value, Error> {
-	value.before(eof).parse(input).map(|(output, _)| output)
+fn parse(input: &str) -> Result<Value, Error<'_>> {
+	all_consuming(value).parse(input).map(|(output, _)| output)
 }
 
 fn load(file: &str) -> String {
@@ -145,7 +124,7 @@ fn load(file: &str) -> String {
 	use std::io::Read;
 
 	let mut f = File::open(file)
-		.expect(&format!("Failed to open the file '{file}'"));
+		.unwrap_or_else(|_| panic!("Failed to open the file '{file}'"));
 	let mut out = String::new();
 	f.read_to_string(&mut out)
 		.expect("Failed to read the file contents");
@@ -155,7 +134,7 @@ fn load(file: &str) -> String {
 fn main() {
 	use std::env::args;
 
-	let Some(file) = args().skip(1).next() else {
+	let Some(file) = args().nth(1) else {
 		eprintln!("Pass a UTF-8 path to a JSON file");
 		return;
 	};
@@ -176,7 +155,40 @@ fn test() {
 		basic
 	);
 
-	assert!(parse(&load("data/widget.json")).is_ok());
-	assert!(parse(&load("data/glossary.json")).is_ok());
-	assert!(parse(&load("data/webapp.json")).is_ok());
+	let data = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/data");
+	assert!(parse(&load(&format!("{data}/widget.json"))).is_ok());
+	assert!(parse(&load(&format!("{data}/glossary.json"))).is_ok());
+	assert!(parse(&load(&format!("{data}/webapp.json"))).is_ok());
+}
+
+#[test]
+fn collections() {
+	let object = |pairs: &[(&str, Value)]| {
+		Value::Object(
+			pairs.iter()
+				.map(|(key, value)| {
+					(key.to_string(), value.clone())
+				})
+				.collect(),
+		)
+	};
+
+	assert_eq!(Ok(Value::Array(vec![])), parse("[]"));
+	assert_eq!(Ok(Value::Array(vec![])), parse("[ ]"));
+	assert_eq!(Ok(Value::Array(vec![Value::Number(1.0)])), parse("[1]"));
+	assert_eq!(Ok(object(&[])), parse("{}"));
+	assert_eq!(Ok(object(&[])), parse("{ }"));
+	assert_eq!(
+		Ok(object(&[("a", Value::Number(1.0))])),
+		parse(r#"{"a":1}"#)
+	);
+	assert_eq!(
+		Ok(object(&[("a", Value::Array(vec![])), ("b", object(&[]))])),
+		parse(r#"{ "a" : [], "b": {} }"#)
+	);
+
+	assert!(parse("[1,]").is_err());
+	assert!(parse(r#"{"a":1,}"#).is_err());
+	assert!(parse("[,]").is_err());
+	assert_eq!(Err(Error::NotEnd { span: "]" }), parse("[1]] x"));
 }
@@ -0,0 +1,364 @@
+//! Concrete parsers which operate on `[u8]` input.
+//!
+//! This module mirrors [`string`][crate::string] for binary data.  All of the
+//! parsers return [`Error`] for easier composition.
+//!
+//! ```rust
+//! use komb::{Parser, bytes::{take, take_while}};
+//!
+//! let p = (b"GET".as_slice(), b' ', take_while(|b| b != b' '));
+//!
+//! let (out, rest) = p.parse(b"GET /index.html HTTP/1.1").unwrap();
+//! assert_eq!(b"/index.html", out.2);
+//! assert_eq!(Ok((&b" HTTP"[..], &b"/1.1"[..])), take(5).parse(rest));
+//! ```
+//!
+//! Binary formats are read with the fixed-width integer parsers:
+//!
+//! ```rust
+//! use komb::{Parser, bytes::{be_u32, le_u16, Error}};
+//!
+//! assert_eq!(Ok((256, &[][..])), be_u32.parse(&[0, 0, 1, 0]));
+//! assert_eq!(Ok((0x0201, &[3][..])), le_u16.parse(&[1, 2, 3]));
+//! assert!(matches!(be_u32.parse(&[0, 1]), Err(Error::End { .. })));
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::{fmt, mem::size_of};
+
+use crate::{combinator::TakeUnits, PResult, ParseError, Parser};
+
+/// The error returned by the parsers in this module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<'a> {
+	/// The parser unexpectedly reached the end of the input.
+	End {
+		/// A zero-width slice which points to the end of the input.
+		span: &'a [u8],
+	},
+	/// The parser failed to match.
+	Unmatched {
+		/// The input prefix which the parser encountered instead of
+		/// what it expected.
+		span: &'a [u8],
+	},
+	/// Returned by [`eof`] when the input isn't empty.
+	NotEnd {
+		/// The first byte of the remaining input.
+		span: &'a [u8],
+	},
+	/// A failure described by a message, for example when a combinator
+	/// rejects the output of a parser.
+	Message {
+		/// The description of the failure.
+		message: &'static str,
+		/// The part of the input where the failure occurred.
+		span: &'a [u8],
+	},
+	/// An error marked by [`cut`][crate::combinator::cut], which stops
+	/// alternatives from being tried.
+	#[cfg(feature = "alloc")]
+	Cut {
+		/// The marked error.
+		error: Box<Error<'a>>,
+	},
+}
+
+impl fmt::Display for Error<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::End { .. } => {
+				f.write_str("Unexpected end of input")
+			}
+			Error::Unmatched { span } => f.write_fmt(format_args!(
+				"Parser failed to match {span:02x?}"
+			)),
+			Error::NotEnd { span } => f.write_fmt(format_args!(
+				"Expected end of input, found {span:02x?}"
+			)),
+			Error::Message { message, .. } => f.write_str(message),
+			#[cfg(feature = "alloc")]
+			Error::Cut { error } => error.fmt(f),
+		}
+	}
+}
+
+impl core::error::Error for Error<'_> {}
+
+impl<'a> Error<'a> {
+	/// Creates a new `End` error which points to the end of `input`.
+	fn end(input: &[u8]) -> Error<'_> {
+		Error::End {
+			span: &input[input.len()..],
+		}
+	}
+
+	/// Returns the part of the input where the error occurred.
+	pub fn span(&self) -> &'a [u8] {
+		match self {
+			Error::End { span }
+			| Error::Unmatched { span }
+			| Error::NotEnd { span }
+			| Error::Message { span, .. } => span,
+			#[cfg(feature = "alloc")]
+			Error::Cut { error } => error.span(),
+		}
+	}
+}
+
+impl<'a> ParseError<&'a [u8]> for Error<'a> {
+	fn message(input: &'a [u8], message: &'static str) -> Self {
+		Error::Message {
+			message,
+			span: &input[..0],
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	fn cut(self) -> Self {
+		match self {
+			Error::Cut { .. } => self,
+			error => Error::Cut {
+				error: Box::new(error),
+			},
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	fn is_cut(&self) -> bool {
+		matches!(self, Error::Cut { .. })
+	}
+}
+
+impl<'a> Parser<'a, &'a [u8], &'a [u8], Error<'a>> for &[u8] {
+	fn parse(
+		&self,
+		input: &'a [u8],
+	) -> PResult<&'a [u8], &'a [u8], Error<'a>> {
+		if input.starts_with(self) {
+			let length = self.len();
+			Ok((&input[..length], &input[length..]))
+		} else if input.len() < self.len() {
+			Err(Error::end(input))
+		} else {
+			Err(Error::Unmatched {
+				span: &input[..self.len()],
+			})
+		}
+	}
+}
+
+impl<'a, const N: usize> Parser<'a, &'a [u8], &'a [u8], Error<'a>>
+	for &[u8; N]
+{
+	fn parse(
+		&self,
+		input: &'a [u8],
+	) -> PResult<&'a [u8], &'a [u8], Error<'a>> {
+		self.as_slice().parse(input)
+	}
+}
+
+impl<'a> Parser<'a, &'a [u8], &'a [u8], Error<'a>> for u8 {
+	fn parse(
+		&self,
+		input: &'a [u8],
+	) -> PResult<&'a [u8], &'a [u8], Error<'a>> {
+		let needle = *self;
+		byte(move |b| b == needle).parse(input)
+	}
+}
+
+/// Matches the `literal` bytes.
+///
+/// Byte slices and arrays are parsers themselves, this function only helps
+/// type inference.
+///
+/// ```rust
+/// use komb::{Parser, bytes::tag};
+///
+/// let p = tag(b"\x89PNG");
+///
+/// assert_eq!(Ok((&b"\x89PNG"[..], &b"\r\n"[..])), p.parse(b"\x89PNG\r\n"));
+/// assert!(p.parse(b"GIF89a").is_err());
+/// ```
+pub fn tag<'a>(
+	literal: &'a [u8],
+) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>> {
+	literal
+}
+
+/// Succeeds if the input is empty.
+///
+/// Otherwise returns [`Error::NotEnd`], which points to the first remaining
+/// byte.
+pub fn eof(input: &[u8]) -> PResult<&[u8], (), Error<'_>> {
+	if input.is_empty() {
+		Ok(((), input))
+	} else {
+		Err(Error::NotEnd { span: &input[..1] })
+	}
+}
+
+/// Takes exactly `length` bytes from the input.  Returns [`Error::End`] if
+/// the input isn't long enough.
+pub fn take<'a>(
+	length: usize,
+) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>> {
+	move |input: &'a [u8]| {
+		if input.len() < length {
+			return Err(Error::end(input));
+		}
+
+		Ok(input.split_at(length))
+	}
+}
+
+/// Takes bytes, see [`length_data`][crate::combinator::length_data].
+impl<'a> TakeUnits<Error<'a>> for &'a [u8] {
+	fn take_units(self, length: usize) -> PResult<Self, Self, Error<'a>> {
+		take(length).parse(self)
+	}
+}
+
+/// Returns the first byte of the input if it satisfies the predicate.
+///
+/// If the predicate fails, [`Error::Unmatched`] is returned.  If the input is
+/// empty, [`Error::End`] is returned.
+pub fn byte<'a, F>(f: F) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>>
+where
+	F: Fn(u8) -> bool + 'a,
+{
+	move |input: &'a [u8]| match input.first() {
+		Some(&b) if f(b) => Ok(input.split_at(1)),
+		Some(_) => Err(Error::Unmatched { span: &input[..1] }),
+		None => Err(Error::end(input)),
+	}
+}
+
+/// Cuts off a non-empty prefix of bytes for which the predicate `f` returns
+/// `true`.
+///
+/// ```rust
+/// use komb::{Parser, bytes::take_while};
+///
+/// let p = take_while(|b| b.is_ascii_digit());
+///
+/// assert_eq!(Ok((&b"42"[..], &b"\0"[..])), p.parse(b"42\0"));
+/// assert!(p.parse(b"\0").is_err());
+/// ```
+pub fn take_while<'a, F>(f: F) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>>
+where
+	F: Fn(u8) -> bool + 'a,
+{
+	move |input: &'a [u8]| {
+		let length = input.iter().take_while(|&&b| f(b)).count();
+
+		match length {
+			0 if input.is_empty() => Err(Error::end(input)),
+			0 => Err(Error::Unmatched { span: &input[..1] }),
+			_ => Ok(input.split_at(length)),
+		}
+	}
+}
+
+/// Matches a non-empty prefix until the first byte which satisfies the
+/// predicate.
+pub fn take_until<'a, F>(f: F) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>>
+where
+	F: Fn(u8) -> bool + 'a,
+{
+	take_while(move |b| !f(b))
+}
+
+/// Matches the bytes in `bytes`.
+///
+/// ```rust
+/// use komb::{Parser, bytes::one_of};
+///
+/// let p = one_of(b"\r\n");
+///
+/// assert_eq!(Ok((&b"\r\n\n"[..], &b"x"[..])), p.parse(b"\r\n\nx"));
+/// ```
+pub fn one_of<'a, 'b: 'a>(
+	bytes: &'b [u8],
+) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>> {
+	take_while(move |b| bytes.contains(&b))
+}
+
+/// Matches the bytes not in `bytes`.
+pub fn none_of<'a, 'b: 'a>(
+	bytes: &'b [u8],
+) -> impl Parser<'a, &'a [u8], &'a [u8], Error<'a>> {
+	take_until(move |b| bytes.contains(&b))
+}
+
+macro_rules! impl_parse_endian {
+	($type:ident, $be:ident, $le:ident) => {
+		#[doc=concat!("Reads a big-endian [`", stringify!($type), "`][prim@", stringify!($type), "].")]
+		///
+		/// Returns [`Error::End`] if the input is too short.
+		pub fn $be(input: &[u8]) -> PResult<&[u8], $type, Error<'_>> {
+			let (bytes, rest) = take(size_of::<$type>()).parse(input)?;
+			let out = $type::from_be_bytes(bytes.try_into().unwrap());
+			Ok((out, rest))
+		}
+
+		#[doc=concat!("Reads a little-endian [`", stringify!($type), "`][prim@", stringify!($type), "].")]
+		///
+		/// Returns [`Error::End`] if the input is too short.
+		pub fn $le(input: &[u8]) -> PResult<&[u8], $type, Error<'_>> {
+			let (bytes, rest) = take(size_of::<$type>()).parse(input)?;
+			let out = $type::from_le_bytes(bytes.try_into().unwrap());
+			Ok((out, rest))
+		}
+	};
+}
+
+impl_parse_endian!(u16, be_u16, le_u16);
+impl_parse_endian!(u32, be_u32, le_u32);
+impl_parse_endian!(u64, be_u64, le_u64);
+impl_parse_endian!(i16, be_i16, le_i16);
+impl_parse_endian!(i32, be_i32, le_i32);
+impl_parse_endian!(i64, be_i64, le_i64);
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn errors() {
+		let input: &[u8] = b"ab";
+
+		assert_eq!(
+			Err(Error::End { span: &input[2..] }),
+			b"abc".parse(input)
+		);
+		assert_eq!(
+			Err(Error::Unmatched { span: &input[..1] }),
+			b'b'.parse(input)
+		);
+		assert_eq!(
+			Err(Error::End { span: &input[2..] }),
+			take(3).parse(input)
+		);
+		assert_eq!(
+			Err(Error::NotEnd { span: &input[..1] }),
+			eof(input)
+		);
+	}
+
+	#[test]
+	fn signed_integers() {
+		assert_eq!(Ok((-2, &[][..])), be_i16.parse(&[0xff, 0xfe]));
+		assert_eq!(
+			Ok((-2, &[][..])),
+			le_i32.parse(&[0xfe, 0xff, 0xff, 0xff])
+		);
+		assert_eq!(
+			Ok((i64::MIN, &[][..])),
+			be_i64.parse(&[0x80, 0, 0, 0, 0, 0, 0, 0])
+		);
+	}
+}
@@ -1,9 +1,26 @@
-use crate::{PResult, Parser};
+use crate::{PResult, ParseError, Parser};
 
 pub struct Choice<T>(T);
 
+/// The collections of parsers [`choice`] accepts: tuples and slices.
+pub trait Alternatives {
+	/// Returns `true` if there are no parsers to choose from.
+	fn is_empty(&self) -> bool;
+}
+
+impl<P> Alternatives for &[P] {
+	fn is_empty(&self) -> bool {
+		<[P]>::is_empty(self)
+	}
+}
+
 /// Picks the first succeeding parser and returns it's output.  If all parsers
-/// fail, the error from the last one is returned.
+/// fail, their errors are combined by [`ParseError::one_of`].  For the errors
+/// of [`string`][crate::string] with the `alloc` feature this lists the
+/// expected literals, otherwise it's the error of the last parser.
+///
+/// An error marked by [`cut`][super::cut] is returned immediately, without
+/// trying the remaining parsers.
 ///
 /// ```rust
 /// use komb::Parser;
@@ -15,33 +32,98 @@ pub struct Choice<T>(T);
 /// assert_eq!(Ok(("c", " rest")), p.parse("c rest"));
 /// assert!(p.parse("d").is_err());
 /// ```
-pub fn choice<'p, P: 'p, I, O, E>(parsers: P) -> impl Parser<'p, I, O, E>
+///
+/// Parsers of the same type can also be passed as a slice, which is useful
+/// when they aren't known at compile time.
+///
+/// # Panics
+///
+/// Panics if `parsers` is an empty slice.
+///
+/// ```rust
+/// use komb::Parser;
+/// use komb::combinator::choice;
+///
+/// let keywords: &[&str] = &["if", "else", "while"];
+/// let p = choice(keywords);
+/// assert_eq!(Ok(("else", " {")), p.parse("else {"));
+/// assert!(p.parse("for").is_err());
+/// ```
+pub fn choice<'p, P: Alternatives + 'p, I, O, E>(
+	parsers: P,
+) -> impl Parser<'p, I, O, E>
 where
 	Choice<P>: Parser<'p, I, O, E>,
 {
+	assert!(
+		!Alternatives::is_empty(&parsers),
+		"choice over an empty slice of parsers"
+	);
 	Choice(parsers)
 }
 
+impl<'a, I, O, E, P> Parser<'a, I, O, E> for Choice<&[P]>
+where
+	I: Copy,
+	P: Parser<'a, I, O, E>,
+	E: ParseError<I>,
+{
+	fn parse(&self, input: I) -> PResult<I, O, E> {
+		// `choice` checks that the slice isn't empty
+		let (last, parsers) = self.0.split_last().unwrap();
+
+		for parser in parsers {
+			match parser.parse(input) {
+				Err(err) if !err.is_cut() => {}
+				result => return result,
+			}
+		}
+
+		match last.parse(input) {
+			Err(err) if !err.is_cut() => {
+				let mut expected =
+					self.0.iter().map(|p| p.expected());
+				Err(E::one_of(input, &mut expected, err))
+			}
+			result => result,
+		}
+	}
+}
+
 macro_rules! impl_choice {
 	($($p:ident $index:tt),*; $lastp:ident $lasti:tt) => {
 
+	impl<$($p,)* $lastp> Alternatives for ($($p,)* $lastp) {
+		fn is_empty(&self) -> bool {
+			false
+		}
+	}
+
 	impl<'a, I, O, $($p,)* $lastp, E> Parser<'a, I, O, E>
 		for Choice<($($p,)* $lastp)>
 	where
 		I: Copy,
 		$($p: Parser<'a, I, O, E>,)*
 		$lastp: Parser<'a, I, O, E>,
+		E: ParseError<I>,
 	{
 		fn parse(&self, input: I) -> PResult<I, O, E> {
 			$(
-			if let Ok((out, rest)) = self.0.$index.parse(input) {
-				return Ok((out, rest));
-			};
+			match self.0.$index.parse(input) {
+				Err(err) if !err.is_cut() => {}
+				result => return result,
+			}
 			)*
 
 			match self.0.$lasti.parse(input) {
-				Ok((out, rest)) => Ok((out, rest)),
-				Err(err) => Err(err),
+				Err(err) if !err.is_cut() => {
+					let expected = [
+						$(self.0.$index.expected(),)*
+						self.0.$lasti.expected(),
+					];
+					Err(E::one_of(input, &mut expected.into_iter(), err))
+				}
+				result => result,
 			}
 		}
 	}
@@ -79,4 +161,74 @@ mod test {
 		let result = parser.parse("cx");
 		assert_eq!(Ok(("c", "x")), result);
 	}
+	#[test]
+	#[should_panic = "choice over an empty slice of parsers"]
+	fn empty_slice() {
+		let parsers: &[&str] = &[];
+		let _ = Parser::<_, _, crate::string::Error>::parse(
+			&choice(parsers),
+			"a",
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn cut_stops_alternatives() {
+		use crate::{combinator::cut, string::Error};
+
+		let parser = choice((cut("ab"), "ac"));
+		assert!(matches!(parser.parse("ac"), Err(Error::Cut { .. })));
+
+		fn ab(input: &str) -> PResult<&str, &str, Error<'_>> {
+			cut("ab").parse(input)
+		}
+		fn ac(input: &str) -> PResult<&str, &str, Error<'_>> {
+			Parser::parse(&"ac", input)
+		}
+		type Rule = fn(&str) -> PResult<&str, &str, Error<'_>>;
+		let parsers: &[Rule] = &[ab, ac];
+		let parser = choice(parsers);
+		assert!(matches!(parser.parse("ac"), Err(Error::Cut { .. })));
+
+		let parser = cut("ab").or("ac");
+		assert!(matches!(parser.parse("ac"), Err(Error::Cut { .. })));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn lists_expected_literals() {
+		use crate::string::Error;
+		use alloc::{string::ToString, vec};
+
+		let parser = choice(("true", "false", "null"));
+		let error = parser.parse("nope").unwrap_err();
+		assert_eq!(
+			Error::Expected {
+				expected: vec![
+					"true".to_string(),
+					"false".to_string(),
+					"null".to_string(),
+				],
+				span: "",
+			},
+			error
+		);
+		assert_eq!(
+			"Expected one of: 'true', 'false', 'null'",
+			error.to_string()
+		);
+
+		let keywords: &[&str] = &["if", "else", "if"];
+		assert_eq!(
+			"Expected one of: 'if', 'else'",
+			choice(keywords).parse("for").unwrap_err().to_string()
+		);
+
+		// a parser which isn't a literal can't be listed
+		let parser = choice(("true", "false".map_out(|s| s)));
+		assert_eq!(
+			Err(Error::Unmatched { span: "maybe" }),
+			parser.parse("maybe")
+		);
+	}
 }
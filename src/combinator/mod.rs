@@ -1,14 +1,24 @@
 //! Type-agnostic combinators which combine other parsers.
 
 mod choice;
+mod permutation;
 mod tuple;
 pub use choice::choice;
+pub use permutation::permutation;
 
-use crate::Parser;
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::cell::Cell;
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
+
+use crate::{progress, PResult, ParseError, Parser, Sliceable};
 
 /// Makes the passed parser optional.  That is, it'll return `Ok((None, input))`
 /// if the underlying parser fails.  The input won't be consumed.
 ///
+/// Errors marked by [`cut`] are replaced too, [`Parser::opt`] returns them.
+///
 /// ```rust
 /// use komb::{Parser, combinator::optional};
 ///
@@ -59,13 +69,229 @@ where
 	}
 }
 
-// TODO: investigate discarding the delimiting parsers errors and returning a
-// custom one instead.  This will allow to mix the error types of the parsers,
-// avoiding the `map_err` transforms required right now.
+/// Runs `parser` without consuming the input.  On success the output is
+/// returned together with the original input, otherwise the error is
+/// returned unchanged.
+///
+/// ```rust
+/// use komb::{Parser, combinator::peek};
+///
+/// let p = peek("if");
+///
+/// assert_eq!(Ok(("if", "if x")), p.parse("if x"));
+/// assert!(p.parse("else").is_err());
+/// ```
+pub fn peek<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, O, E>
+where
+	I: Copy + 'a,
+	O: 'a,
+	E: 'a,
+{
+	move |input| {
+		let (output, _) = parser.parse(input)?;
+		Ok((output, input))
+	}
+}
+
+/// Always succeeds with a clone of `value` without consuming the input.
+///
+/// It can serve as the last alternative of a [`choice`] to provide a
+/// default.
+///
+/// ```rust
+/// use komb::{Parser, combinator::{choice, success}};
+///
+/// let p = choice(("on".value(true), "off".value(false), success(false)));
+///
+/// assert_eq!(Ok((true, "")), p.parse("on"));
+/// assert_eq!(Ok((false, "maybe")), p.parse("maybe"));
+/// ```
+pub fn success<'a, I, O, E>(value: O) -> impl Parser<'a, I, O, E>
+where
+	I: 'a,
+	O: Clone + 'a,
+	E: 'a,
+{
+	move |input| Ok((value.clone(), input))
+}
+
+/// Always fails with [`ParseError::message`] at the start of the input.
+///
+/// ```rust
+/// use komb::{PResult, Parser, combinator::fail, string::{u8, Error}};
+///
+/// fn percent(input: &str) -> PResult<&str, u8, Error<'_>> {
+///     let (n, rest) = u8(input)?;
+///     if n > 100 {
+///         return fail("percentage over 100").parse(input);
+///     }
+///     Ok((n, rest))
+/// }
+///
+/// assert_eq!(Ok((42, "")), percent("42"));
+/// assert_eq!(
+///     Err(Error::Message { message: "percentage over 100", span: "" }),
+///     percent("142"),
+/// );
+/// ```
+pub fn fail<'a, I, O, E>(message: &'static str) -> impl Parser<'a, I, O, E>
+where
+	I: 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |input| Err(E::message(input, message))
+}
+
+/// Returns the part of the input which `parser` consumed instead of its
+/// output.
+///
+/// ```rust
+/// use komb::{PResult, Parser, combinator::recognize, string::u32};
+///
+/// let p = recognize((u32, ".", u32));
+/// assert_eq!(Ok(("1.25", " rest")), p.parse("1.25 rest"));
+///
+/// fn digit(input: &[u8]) -> PResult<&[u8], u8, ()> {
+///     match input.split_first() {
+///         Some((byte, rest)) if byte.is_ascii_digit() => Ok((*byte, rest)),
+///         _ => Err(()),
+///     }
+/// }
+///
+/// let p = recognize((digit, digit));
+/// assert_eq!(Ok((&b"12"[..], &b"3"[..])), p.parse(b"123"));
+/// ```
+pub fn recognize<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, I, E>
+where
+	I: Sliceable + 'a,
+{
+	move |input: I| {
+		let (_, rest) = parser.parse(input)?;
+		Ok((input.consumed(rest), rest))
+	}
+}
+
+/// Returns both the output of `parser` and the part of the input it
+/// consumed.
+///
+/// ```rust
+/// use komb::{Parser, combinator::with_recognized, string::u32};
+///
+/// let p = with_recognized(u32);
+///
+/// assert_eq!(Ok(((42, "0042"), " rest")), p.parse("0042 rest"));
+/// ```
+pub fn with_recognized<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, (O, I), E>
+where
+	I: Sliceable + 'a,
+{
+	move |input: I| {
+		let (output, rest) = parser.parse(input)?;
+		Ok(((output, input.consumed(rest)), rest))
+	}
+}
+
+/// Marks the error of `parser` as fatal, so that [`choice`] and
+/// [`Parser::or`] return it instead of trying the other alternatives.
+///
+/// This is useful once the input is known to belong to one alternative, for
+/// example after an opening parenthesis.  Then an error inside it isn't
+/// masked by a fallback.
+///
+/// The combinators which otherwise treat an error as "no match" return a cut
+/// error too: [`Parser::opt`], [`Parser::opt_flagged`], [`many_m_n`] with
+/// the rest of the `many` family, [`separated_list0`], [`separated_list1`],
+/// [`fold_m_n`], [`try_fold`] and [`permutation`].  [`optional`] and
+/// [`fold`] don't look at the error.
+///
+/// The errors of [`string`][crate::string] and [`bytes`][crate::bytes] can
+/// only be marked with the `alloc` feature.  Without it `cut` returns them
+/// unchanged.
+///
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use komb::{ParseError, Parser};
+/// use komb::combinator::{choice, cut, delimited, many0};
+/// use komb::string::u32;
+///
+/// let p = choice((delimited("(", u32, ")"), "(".value(0)));
+/// assert_eq!(Ok((0, "x)")), p.parse("(x)"));
+///
+/// let p = choice((delimited("(", cut(u32), ")"), "(".value(0)));
+/// assert_eq!(Ok((1, "")), p.parse("(1)"));
+/// assert!(p.parse("(x)").unwrap_err().is_cut());
+///
+/// let p = many0(delimited("(", cut(u32), ")"));
+/// assert_eq!(Ok((vec![1], "x")), p.parse("(1)x"));
+/// assert!(p.parse("(1)(x)").unwrap_err().is_cut());
+/// # }
+/// ```
+pub fn cut<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, O, E>
+where
+	I: 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	parser.map_err(E::cut)
+}
+
+/// Adds a frame named `label` to the error of `parser` using
+/// [`ParseError::context`].
+///
+/// Wrapping the rules of a grammar builds a trace of the rules which were
+/// being parsed when a nested parser failed.
+///
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use komb::{PResult, Parser, combinator::{delimited, stacked}};
+/// use komb::string::{u32, Error};
+///
+/// fn element(input: &str) -> PResult<&str, u32, Error<'_>> {
+///     stacked("element", stacked("number", u32)).parse(input)
+/// }
+///
+/// fn array(input: &str) -> PResult<&str, u32, Error<'_>> {
+///     stacked("array", delimited("[", element, "]")).parse(input)
+/// }
+///
+/// let error = stacked("value", array).parse("[4294967296]").unwrap_err();
+///
+/// assert_eq!(
+///     vec!["value", "array", "element", "number"],
+///     error.context_stack(),
+/// );
+/// assert_eq!("4294967296", error.span());
+/// assert!(error.to_string().starts_with("value → array → element → number → "));
+/// # }
+/// ```
+pub fn stacked<'a, I, O, E>(
+	label: &'static str,
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, O, E>
+where
+	I: 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	parser.map_err(move |error: E| error.context(label))
+}
+
 /// Matches the `content` parser with `left` and `right` at the start and the
 /// end respectively.  If any one of the three parsers fails, this error is
 /// returned.  Otherwise the input of `content` is returned.
 ///
+/// All three parsers have to return the same error type, see
+/// [`delimited_mixed`] for delimiters with other error types.
+///
 /// ```rust
 /// use komb::{Parser, combinator::delimited};
 /// use komb::string::{alphabetic, or0};
@@ -88,18 +314,180 @@ where
 	OR: 'a,
 	E: 'a,
 {
-	(left, content, right).map_out(|tuple| tuple.1)
+	preceded(left, terminated(content, right))
+}
+
+/// Like [`delimited`], but `left` and `right` may return any error type.
+///
+/// Their errors are discarded and replaced with [`ParseError::message`]s
+/// "expected opening delimiter" and "expected closing delimiter", which
+/// point to where the delimiter was expected.  An error of `content` is
+/// returned unchanged.
+///
+/// ```rust
+/// use komb::{PResult, Parser, combinator::delimited_mixed};
+/// use komb::string::{u32, Error};
+///
+/// fn open(input: &str) -> PResult<&str, (), ()> {
+///     input.strip_prefix("<<").map(|rest| ((), rest)).ok_or(())
+/// }
+///
+/// let p = delimited_mixed(open, u32, ">>");
+///
+/// assert_eq!(Ok((1, "")), p.parse("<<1>>"));
+/// assert_eq!(
+///     Err(Error::Message {
+///         message: "expected closing delimiter",
+///         span: "",
+///     }),
+///     p.parse("<<1>"),
+/// );
+/// assert!(matches!(
+///     p.parse("(1)"),
+///     Err(Error::Message { message: "expected opening delimiter", .. }),
+/// ));
+/// ```
+pub fn delimited_mixed<'a, I, OL, EL, O, OR, ER, E>(
+	left: impl Parser<'a, I, OL, EL> + 'a,
+	content: impl Parser<'a, I, O, E> + 'a,
+	right: impl Parser<'a, I, OR, ER> + 'a,
+) -> impl Parser<'a, I, O, E>
+where
+	I: Copy + 'a,
+	OL: 'a,
+	EL: 'a,
+	O: 'a,
+	OR: 'a,
+	ER: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |input| {
+		let Ok((_, rest)) = left.parse(input) else {
+			return Err(E::message(
+				input,
+				"expected opening delimiter",
+			));
+		};
+		let (out, rest) = content.parse(rest)?;
+		let Ok((_, rest)) = right.parse(rest) else {
+			return Err(E::message(
+				rest,
+				"expected closing delimiter",
+			));
+		};
+
+		Ok((out, rest))
+	}
+}
+
+/// Parses `prefix` and then `content`, discarding the output of `prefix`.  If
+/// either parser fails, its error is returned.
+///
+/// ```rust
+/// use komb::{Parser, combinator::preceded, string::any_char};
+///
+/// let p = preceded("<<", any_char);
+///
+/// assert_eq!(Ok(("x", "")), p.parse("<<x"));
+/// assert!(p.parse("<x").is_err());
+/// ```
+pub fn preceded<'a, I, OP, O, E>(
+	prefix: impl Parser<'a, I, OP, E> + 'a,
+	content: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, O, E>
+where
+	OP: 'a,
+	O: 'a,
+{
+	prefix.and_then(content)
+}
+
+/// Parses `content` and then `suffix`, discarding the output of `suffix`.  If
+/// either parser fails, its error is returned.
+///
+/// ```rust
+/// use komb::{Parser, combinator::terminated, string::alphabetic};
+///
+/// let p = terminated(alphabetic, ";");
+///
+/// assert_eq!(Ok(("stmt", " rest")), p.parse("stmt; rest"));
+/// assert!(p.parse("stmt rest").is_err());
+/// ```
+pub fn terminated<'a, I, O, OS, E>(
+	content: impl Parser<'a, I, O, E> + 'a,
+	suffix: impl Parser<'a, I, OS, E> + 'a,
+) -> impl Parser<'a, I, O, E>
+where
+	O: 'a,
+	OS: 'a,
+{
+	content.before(suffix)
+}
+
+/// Parses `first` and then `second`, returning both outputs.  If either
+/// parser fails, its error is returned.
+///
+/// This is the same as the `(first, second)` tuple parser.
+///
+/// ```rust
+/// use komb::{Parser, combinator::pair, string::{alphabetic, u32}};
+///
+/// let p = pair(alphabetic, u32);
+///
+/// assert_eq!(Ok((("abc", 12), " rest")), p.parse("abc12 rest"));
+/// assert!(p.parse("abc rest").is_err());
+/// ```
+pub fn pair<'a, I, O1, O2, E>(
+	first: impl Parser<'a, I, O1, E> + 'a,
+	second: impl Parser<'a, I, O2, E> + 'a,
+) -> impl Parser<'a, I, (O1, O2), E>
+where
+	I: Copy + 'a,
+	O1: 'a,
+	O2: 'a,
+	E: 'a,
+{
+	(first, second)
+}
+
+/// Parses `first`, `sep` and `second`, discarding the output of `sep`.  If
+/// any one of the three parsers fails, its error is returned.
+///
+/// ```rust
+/// use komb::{Parser, combinator::separated_pair, string::alphabetic};
+///
+/// let p = separated_pair(alphabetic, ":", alphabetic);
+///
+/// assert_eq!(Ok((("name", "komb"), "")), p.parse("name:komb"));
+/// assert!(p.parse("name=komb").is_err());
+/// ```
+pub fn separated_pair<'a, I, O1, OS, O2, E>(
+	first: impl Parser<'a, I, O1, E> + 'a,
+	sep: impl Parser<'a, I, OS, E> + 'a,
+	second: impl Parser<'a, I, O2, E> + 'a,
+) -> impl Parser<'a, I, (O1, O2), E>
+where
+	I: Copy + 'a,
+	O1: 'a,
+	OS: 'a,
+	O2: 'a,
+	E: 'a,
+{
+	pair(terminated(first, sep), second)
 }
 
 /// Applies `parser` and passes its output to the `apply`, which can modify the
 /// `acc` accumulator.  Useful for building strings, vectors of AST elements,
 /// and so on.
 ///
-/// Note that `fold` will stop when `parser` returns an error.  This means that
-/// if `parser` never errors out, `fold` will hang forever.
+/// `fold` stops when `parser` returns an error or on a match which consumes no
+/// input, see [progress][crate::Sliceable#progress].  So a parser which never
+/// fails, like [`optional`], doesn't make it loop forever.
+///
+/// Errors marked by [`cut`] end the loop too, [`try_fold`] returns them.
 ///
 /// ```rust
-/// use komb::{Parser, combinator::fold, string::any_char};
+/// use komb::{Parser, combinator::{fold, optional}, string::any_char};
 ///
 /// let p = fold(
 ///     any_char.before(","),
@@ -112,6 +500,9 @@ where
 /// let (output, _) = p.parse("a,b,c,d,").unwrap();
 ///
 /// assert_eq!(vec!["a", "b", "c", "d"], output);
+///
+/// let p = fold(optional("ab"), 0, |n, _| *n += 1);
+/// assert_eq!(Ok((2, "c")), p.parse("ababc"));
 /// ```
 pub fn fold<'a, I, O, OX, E, F>(
 	parser: impl Parser<'a, I, O, E>,
@@ -119,7 +510,7 @@ pub fn fold<'a, I, O, OX, E, F>(
 	apply: F,
 ) -> impl Parser<'a, I, OX, E>
 where
-	I: Copy + 'a,
+	I: Sliceable + 'a,
 	O: 'a,
 	OX: Clone + 'a,
 	E: 'a,
@@ -133,6 +524,9 @@ where
 			let Ok((output, rest)) = parser.parse(input) else {
 				break;
 			};
+			if rest.len() == input.len() {
+				break;
+			}
 			input = rest;
 			apply(&mut acc, output);
 		}
@@ -140,3 +534,899 @@ where
 		Ok((acc, input))
 	}
 }
+
+/// Like [`fold`], but applies `parser` at least `min` and at most `max` times.
+///
+/// If `parser` fails before matching `min` times, its error is returned.
+/// Once `max` outputs are folded, `parser` isn't applied anymore, so the
+/// input after the `max`th match is left untouched.  A match which consumes
+/// no input counts as a failure, see [progress][crate::Sliceable#progress].
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`.
+///
+/// ```rust
+/// use komb::{Parser, combinator::fold_m_n};
+/// use komb::string::{any_char, take_while_m_n};
+///
+/// let digit = take_while_m_n(1, 1, |c| c.is_ascii_hexdigit());
+/// // the digit is always valid, so this can't panic
+/// let hex = digit.map_out(|d| u32::from_str_radix(d, 16).unwrap());
+/// let p = fold_m_n(2, 4, hex, 0, |acc, digit| *acc = *acc * 16 + digit);
+///
+/// assert_eq!(Ok((0xbeef, "0")), p.parse("beef0"));
+/// assert_eq!(Ok((0xab, "")), p.parse("ab"));
+/// assert!(p.parse("a").is_err());
+/// assert!(p.parse("ax").is_err());
+///
+/// let exact = fold_m_n(2, 2, any_char, String::new(), |s, c| s.push_str(c));
+/// assert_eq!(Ok(("ab".to_owned(), "c")), exact.parse("abc"));
+///
+/// let count = fold_m_n(0, 2, "x", 0, |n, _| *n += 1);
+/// assert_eq!(Ok((0, "abc")), count.parse("abc"));
+/// assert_eq!(Ok((2, "x")), count.parse("xxx"));
+/// ```
+pub fn fold_m_n<'a, I, O, OX, E, F>(
+	min: usize,
+	max: usize,
+	parser: impl Parser<'a, I, O, E>,
+	acc: OX,
+	apply: F,
+) -> impl Parser<'a, I, OX, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OX: Clone + 'a,
+	E: ParseError<I> + 'a,
+	F: Fn(&mut OX, O) + 'a,
+{
+	assert!(min <= max);
+
+	move |input| {
+		let mut acc = acc.clone();
+		let mut input = input;
+
+		for i in 0..max {
+			match progress(input, parser.parse(input)) {
+				Ok((output, rest)) => {
+					input = rest;
+					apply(&mut acc, output);
+				}
+				Err(err) if i < min || err.is_cut() => {
+					return Err(err)
+				}
+				Err(_) => break,
+			}
+		}
+
+		Ok((acc, input))
+	}
+}
+
+/// Like [`fold`], but `apply` can fail.
+///
+/// If `apply` returns an error, `try_fold` stops and returns it.  This allows
+/// rejecting the input based on the accumulated state, like duplicate keys in
+/// a map.  Like `fold`, it also stops on a match which consumes no input.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use komb::{Parser, combinator::try_fold, string::{alphabetic, u32, Error}};
+///
+/// let pair = (alphabetic, "=", u32, ",").map_out(|(k, _, v, _)| (k, v));
+/// let p = try_fold(pair, HashMap::new(), |map, (key, value)| {
+///     match map.insert(key, value) {
+///         None => Ok(()),
+///         Some(_) => Err(Error::Message {
+///             message: "duplicate key",
+///             span: key,
+///         }),
+///     }
+/// });
+///
+/// let (map, _) = p.parse("a=1,b=2,").unwrap();
+/// assert_eq!(Some(&2), map.get("b"));
+/// assert_eq!(
+///     Err(Error::Message { message: "duplicate key", span: "a" }),
+///     p.parse("a=1,b=2,a=3,"),
+/// );
+/// ```
+pub fn try_fold<'a, I, O, OX, E, F>(
+	parser: impl Parser<'a, I, O, E>,
+	acc: OX,
+	apply: F,
+) -> impl Parser<'a, I, OX, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OX: Clone + 'a,
+	E: ParseError<I> + 'a,
+	F: Fn(&mut OX, O) -> Result<(), E> + 'a,
+{
+	move |input| {
+		let mut acc = acc.clone();
+		let mut input = input;
+
+		loop {
+			let (output, rest) =
+				match progress(input, parser.parse(input)) {
+					Ok(result) => result,
+					Err(err) if err.is_cut() => {
+						return Err(err)
+					}
+					Err(_) => break,
+				};
+			input = rest;
+			apply(&mut acc, output)?;
+		}
+
+		Ok((acc, input))
+	}
+}
+
+/// Applies `parser` `n + 1` times and returns the output of the last
+/// application, discarding the previous ones.  That is, `n` is zero-based.
+///
+/// The input is consumed up to and including the `n`th match.  If the parser
+/// fails before reaching it, the error is returned.
+///
+/// ```rust
+/// use komb::{Parser, combinator::nth, string::alphanumeric};
+///
+/// let p = nth(2, alphanumeric.before(","));
+///
+/// assert_eq!(Ok(("ccc", "dd,")), p.parse("a,bb,ccc,dd,"));
+/// assert!(p.parse("a,bb,").is_err());
+/// ```
+pub fn nth<'a, I, O, E>(
+	n: usize,
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, O, E>
+where
+	I: Copy + 'a,
+	O: 'a,
+	E: 'a,
+{
+	move |input| {
+		let mut input = input;
+
+		for _ in 0..n {
+			let (_, rest) = parser.parse(input)?;
+			input = rest;
+		}
+
+		parser.parse(input)
+	}
+}
+
+/// Applies `parser` zero or more times and collects the outputs into a
+/// [`Vec`].
+///
+/// This parser never fails: if `parser` doesn't match even once, including
+/// on empty input, an empty vector is returned and no input is consumed.
+///
+/// A match which consumes no input stops the repetition, see
+/// [progress][crate::Sliceable#progress].
+///
+/// ```rust
+/// use komb::{Parser, combinator::many0, string::{alphabetic, or0}};
+///
+/// let p = many0("ab");
+///
+/// assert_eq!(Ok((vec!["ab", "ab"], "c")), p.parse("ababc"));
+/// assert_eq!(Ok((vec![], "c")), p.parse("c"));
+/// assert_eq!(Ok((vec![], "")), p.parse(""));
+///
+/// // `or0` never fails, but `many0` still terminates
+/// let p = many0(or0(alphabetic));
+/// assert_eq!(Ok((vec!["ab"], "12")), p.parse("ab12"));
+/// assert_eq!(Ok((vec![], "12")), p.parse("12"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many0<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	many_m_n(0, usize::MAX, parser)
+}
+
+/// Applies `parser` one or more times and collects the outputs into a
+/// [`Vec`].
+///
+/// If the first application fails, including on empty input, its error is
+/// returned.  After that `many1` behaves like [`many0`].
+///
+/// ```rust
+/// use komb::{Parser, combinator::many1, string::alphabetic};
+///
+/// let p = many1(alphabetic.before(","));
+///
+/// assert_eq!(Ok((vec!["a", "b", "c"], "")), p.parse("a,b,c,"));
+/// assert_eq!(Ok((vec!["a"], "b")), p.parse("a,b"));
+/// assert!(p.parse(",").is_err());
+/// assert!(p.parse("").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many1<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	many_m_n(1, usize::MAX, parser)
+}
+
+/// Applies `parser` at least `n` times and collects the outputs into a
+/// [`Vec`].
+///
+/// If `parser` fails before matching `n` times, its error is returned.  With
+/// `n` equal to zero it is the same as [`many0`], so empty input is only
+/// accepted in that case.
+///
+/// ```rust
+/// use komb::{Parser, combinator::many_n};
+///
+/// let p = many_n(2, "ab");
+///
+/// assert_eq!(Ok((vec!["ab", "ab", "ab"], "c")), p.parse("abababc"));
+/// assert!(p.parse("abc").is_err());
+/// assert!(p.parse("").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many_n<'a, I, O, E>(
+	n: usize,
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	many_m_n(n, usize::MAX, parser)
+}
+
+/// Applies `parser` at least `min` and at most `max` times and collects the
+/// outputs into a [`Vec`].
+///
+/// If `parser` fails before matching `min` times, its error is returned.
+/// Once `max` outputs are collected, `parser` isn't applied anymore, even if
+/// it would match.  Empty input is only accepted if `min` is zero.
+///
+/// A match which consumes no input counts as a failure, see
+/// [progress][crate::Sliceable#progress].
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`.
+///
+/// ```rust
+/// use komb::{Parser, combinator::many_m_n};
+/// use komb::string::{alphabetic, or0, Error};
+///
+/// let p = many_m_n(1, 2, "ab");
+///
+/// assert_eq!(Ok((vec!["ab"], "c")), p.parse("abc"));
+/// assert_eq!(Ok((vec!["ab", "ab"], "abc")), p.parse("abababc"));
+/// assert!(p.parse("c").is_err());
+///
+/// let p = many_m_n(2, 3, or0(alphabetic));
+/// assert_eq!(
+///     Err(Error::Message { message: "parser made no progress", span: "" }),
+///     p.parse("ab1"),
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many_m_n<'a, I, O, E>(
+	min: usize,
+	max: usize,
+	parser: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	assert!(min <= max);
+
+	move |input| {
+		let mut output = Vec::new();
+		let mut input = input;
+
+		while output.len() < max {
+			match progress(input, parser.parse(input)) {
+				Ok((out, rest)) => {
+					output.push(out);
+					input = rest;
+				}
+				Err(err) if output.len() < min
+					|| err.is_cut() =>
+				{
+					return Err(err)
+				}
+				Err(_) => break,
+			}
+		}
+
+		Ok((output, input))
+	}
+}
+
+/// Applies `parser` until `till` matches and returns the collected outputs
+/// together with the output of `till`.
+///
+/// Before each application of `parser`, `till` is tried first.  If `parser`
+/// fails before `till` matches, the error of `parser` is returned, and so is
+/// the error for a match which consumes no input, see
+/// [progress][crate::Sliceable#progress].
+///
+/// ```rust
+/// use komb::{Parser, combinator::many_till, string::any_char};
+///
+/// let p = "/*".and_then(many_till(any_char, "*/"));
+///
+/// assert_eq!(
+///     Ok(((vec!["a", " ", "*"], "*/"), " rest")),
+///     p.parse("/*a **/ rest"),
+/// );
+/// assert_eq!(Ok(((vec![], "*/"), "")), p.parse("/**/"));
+/// assert!(p.parse("/* unterminated").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many_till<'a, I, O, OT, E>(
+	parser: impl Parser<'a, I, O, E> + 'a,
+	till: impl Parser<'a, I, OT, E> + 'a,
+) -> impl Parser<'a, I, (Vec<O>, OT), E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OT: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |input| {
+		let mut output = Vec::new();
+		let mut input = input;
+
+		loop {
+			if let Ok((end, rest)) = till.parse(input) {
+				return Ok(((output, end), rest));
+			}
+
+			let (out, rest) = progress(input, parser.parse(input))?;
+			output.push(out);
+			input = rest;
+		}
+	}
+}
+
+/// Applies `item` as few times as possible, so that `stop` matches right
+/// after it.  This is the lazy quantifier, like `.*?` in regular
+/// expressions.
+///
+/// This is the same as [`many_till`], see [`many_greedy`] for the greedy
+/// counterpart.  `stop` is tried before every `item`, so the cost is one
+/// extra `stop` attempt per repetition.
+///
+/// ```rust
+/// use komb::{Parser, combinator::{many_greedy, many_lazy}, string::any_char};
+///
+/// let lazy = many_lazy(any_char, ",");
+/// assert_eq!(Ok(((vec!["a"], ","), "b,c")), lazy.parse("a,b,c"));
+///
+/// let greedy = many_greedy(any_char, ",");
+/// assert_eq!(Ok(((vec!["a", ",", "b"], ","), "c")), greedy.parse("a,b,c"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many_lazy<'a, I, O, OS, E>(
+	item: impl Parser<'a, I, O, E> + 'a,
+	stop: impl Parser<'a, I, OS, E> + 'a,
+) -> impl Parser<'a, I, (Vec<O>, OS), E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OS: 'a,
+	E: ParseError<I> + 'a,
+{
+	many_till(item, stop)
+}
+
+/// Applies `item` as many times as possible, so that `stop` still matches
+/// right after it.  This is the greedy quantifier, like `.*` in regular
+/// expressions.
+///
+/// First `item` is applied until it fails or stops consuming input.  Then
+/// `stop` is tried after the last match, and, if it fails, after the one
+/// before it, and so on until the start of the input.  If `stop` never
+/// matches, its last error is returned.
+///
+/// All of the outputs and the positions after them are kept until `stop`
+/// matches, and `stop` may be tried once per collected output.  Prefer
+/// [`many_lazy`] or [`many0`] when the grammar doesn't need backtracking.
+///
+/// ```rust
+/// use komb::{Parser, combinator::many_greedy, string::any_char};
+///
+/// let p = many_greedy(any_char, ".");
+///
+/// assert_eq!(Ok(((vec!["a", ".", "b"], "."), "c")), p.parse("a.b.c"));
+/// assert_eq!(Ok(((vec![], "."), "")), p.parse("."));
+/// assert!(p.parse("abc").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn many_greedy<'a, I, O, OS, E>(
+	item: impl Parser<'a, I, O, E> + 'a,
+	stop: impl Parser<'a, I, OS, E> + 'a,
+) -> impl Parser<'a, I, (Vec<O>, OS), E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OS: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |input: I| {
+		let mut output = Vec::new();
+		let mut positions = Vec::from([input]);
+		let mut input = input;
+
+		loop {
+			let (out, rest) =
+				match progress(input, item.parse(input)) {
+					Ok(result) => result,
+					Err(err) if err.is_cut() => {
+						return Err(err)
+					}
+					Err(_) => break,
+				};
+			output.push(out);
+			positions.push(rest);
+			input = rest;
+		}
+
+		let mut error = None;
+		while let Some(position) = positions.pop() {
+			match stop.parse(position) {
+				Ok((end, rest)) => {
+					output.truncate(positions.len());
+					return Ok(((output, end), rest));
+				}
+				Err(err) => error = Some(err),
+			}
+		}
+
+		Err(error.unwrap())
+	}
+}
+
+/// Applies `body` as long as `condition` matches before it and collects the
+/// outputs into a [`Vec`].
+///
+/// `condition` is only a lookahead: its output is discarded and the input
+/// it matched isn't consumed.  Once it fails, the outputs are returned.  If
+/// `body` fails after `condition` matched, the error of `body` is returned,
+/// and so is the error for a match which consumes no input, see
+/// [progress][crate::Sliceable#progress].
+///
+/// ```rust
+/// use komb::{Parser, combinator::while_, string::{char, u32}};
+///
+/// let digit = char(|ch| ch.is_ascii_digit());
+/// let p = while_(digit, u32.before(' '));
+///
+/// assert_eq!(Ok((vec![1, 22, 3], "end")), p.parse("1 22 3 end"));
+/// assert_eq!(Ok((vec![], "end")), p.parse("end"));
+/// assert!(p.parse("1 2").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn while_<'a, I, OC, O, E>(
+	condition: impl Parser<'a, I, OC, E> + 'a,
+	body: impl Parser<'a, I, O, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	OC: 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |mut input| {
+		let mut output = Vec::new();
+
+		while condition.parse(input).is_ok() {
+			let (out, rest) = progress(input, body.parse(input))?;
+			output.push(out);
+			input = rest;
+		}
+
+		Ok((output, input))
+	}
+}
+
+/// Parses zero or more `item`s separated by `sep` and collects them into a
+/// [`Vec`].  The outputs of `sep` are discarded.
+///
+/// This parser never fails: if there is no `item` at the start of the input,
+/// an empty vector is returned.  A trailing separator which isn't followed by
+/// an `item` is not consumed.
+///
+/// ```rust
+/// use komb::{Parser, combinator::separated_list0, string::i32};
+///
+/// let p = separated_list0(i32, ",");
+///
+/// assert_eq!(Ok((vec![1, 2, 3], "")), p.parse("1,2,3"));
+/// assert_eq!(Ok((vec![1, 2], ",")), p.parse("1,2,"));
+/// assert_eq!(Ok((vec![], "x")), p.parse("x"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn separated_list0<'a, I, O, OS, E>(
+	item: impl Parser<'a, I, O, E> + 'a,
+	sep: impl Parser<'a, I, OS, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OS: 'a,
+	E: ParseError<I> + 'a,
+{
+	let list = separated_list1(item, sep);
+
+	move |input| match list.parse(input) {
+		Ok((output, rest)) => Ok((output, rest)),
+		Err(err) if err.is_cut() => Err(err),
+		Err(_) => Ok((Vec::new(), input)),
+	}
+}
+
+/// Parses one or more `item`s separated by `sep` and collects them into a
+/// [`Vec`].  The outputs of `sep` are discarded.
+///
+/// If the first `item` fails, its error is returned.  Otherwise it behaves
+/// like [`separated_list0`].
+///
+/// ```rust
+/// use komb::{Parser, combinator::separated_list1, string::alphabetic};
+///
+/// let p = separated_list1(alphabetic, ", ");
+///
+/// assert_eq!(Ok((vec!["a", "b"], ", 1")), p.parse("a, b, 1"));
+/// assert!(p.parse("1, a").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn separated_list1<'a, I, O, OS, E>(
+	item: impl Parser<'a, I, O, E> + 'a,
+	sep: impl Parser<'a, I, OS, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	OS: 'a,
+	E: ParseError<I> + 'a,
+{
+	move |input| {
+		let (first, mut input) = item.parse(input)?;
+		let mut output = Vec::new();
+		output.push(first);
+
+		loop {
+			let rest = match sep.parse(input) {
+				Ok((_, rest)) => rest,
+				Err(err) if err.is_cut() => return Err(err),
+				Err(_) => break,
+			};
+			let (out, rest) = match item.parse(rest) {
+				Ok(result) => result,
+				Err(err) if err.is_cut() => return Err(err),
+				Err(_) => break,
+			};
+			if rest.len() == input.len() {
+				break;
+			}
+
+			output.push(out);
+			input = rest;
+		}
+
+		Ok((output, input))
+	}
+}
+
+/// Parses zero or more `item`s separated by `sep` between `open` and
+/// `close`, like `[1, 2, 3]`.
+///
+/// This is [`separated_list0`] wrapped in [`delimited`].  A trailing
+/// separator is rejected, since `close` doesn't match it.  Whitespace isn't
+/// handled, so it has to be a part of the parsers.
+///
+/// ```rust
+/// use komb::{Parser, combinator::delimited_list, string::u32};
+///
+/// let p = delimited_list('[', u32, ',', ']');
+///
+/// assert_eq!(Ok((vec![1, 2, 3], "")), p.parse("[1,2,3]"));
+/// assert_eq!(Ok((vec![1], "")), p.parse("[1]"));
+/// assert_eq!(Ok((vec![], "")), p.parse("[]"));
+/// assert!(p.parse("[1,]").is_err());
+/// assert!(p.parse("[1").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn delimited_list<'a, I, OL, O, OS, OR, E>(
+	open: impl Parser<'a, I, OL, E> + 'a,
+	item: impl Parser<'a, I, O, E> + 'a,
+	sep: impl Parser<'a, I, OS, E> + 'a,
+	close: impl Parser<'a, I, OR, E> + 'a,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Sliceable + 'a,
+	OL: 'a,
+	O: 'a,
+	OS: 'a,
+	OR: 'a,
+	E: ParseError<I> + 'a,
+{
+	delimited(open, separated_list0(item, sep), close)
+}
+
+/// Applies `parser` exactly `n` times and collects the outputs into a
+/// [`Vec`].
+///
+/// If any of the applications fails, its error is returned: unlike
+/// [`many_m_n`], fewer than `n` matches is an error.
+///
+/// ```rust
+/// use komb::{Parser, combinator::count, string::take};
+///
+/// let p = count(take(2), 3);
+///
+/// assert_eq!(Ok((vec!["aa", "bb", "cc"], "dd")), p.parse("aabbccdd"));
+/// assert!(p.parse("aabb").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn count<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E>,
+	n: usize,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Copy + 'a,
+	O: 'a,
+	E: 'a,
+{
+	move |input| {
+		let mut output = Vec::with_capacity(n);
+		let mut input = input;
+
+		for _ in 0..n {
+			let (out, rest) = parser.parse(input)?;
+			output.push(out);
+			input = rest;
+		}
+
+		Ok((output, input))
+	}
+}
+
+/// Runs `count_parser` and then applies `item_parser` as many times as it
+/// returned, collecting the outputs into a [`Vec`].
+///
+/// This is the usual encoding of lists in binary formats, where the items
+/// are prefixed by their number.  If any of the items fails, its error is
+/// returned, just like with [`count`].
+///
+/// ```rust
+/// use komb::{Parser, bytes::{be_u16, be_u32}, combinator::length_count};
+///
+/// let p = length_count(be_u32.map_out(|n| n as usize), be_u16);
+///
+/// let input: &[u8] = &[0, 0, 0, 2, 0, 1, 1, 0, 0xff];
+/// assert_eq!(Ok((vec![1, 256], &[0xff][..])), p.parse(input));
+/// assert!(p.parse(&[0, 0, 0, 3, 0, 1]).is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn length_count<'a, I, O, E>(
+	count_parser: impl Parser<'a, I, usize, E>,
+	item_parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, Vec<O>, E>
+where
+	I: Copy + 'a,
+	O: 'a,
+	E: 'a,
+{
+	move |input| {
+		let (n, mut input) = count_parser.parse(input)?;
+		// The count comes from the input, so it can't be trusted with
+		// the allocation.
+		let mut output = Vec::with_capacity(n.min(64));
+
+		for _ in 0..n {
+			let (out, rest) = item_parser.parse(input)?;
+			output.push(out);
+			input = rest;
+		}
+
+		Ok((output, input))
+	}
+}
+
+/// Inputs which [`length_data`] can take a number of units from.
+///
+/// This is implemented for `&str`, which takes characters, and for `&[u8]`,
+/// which takes bytes.
+pub trait TakeUnits<E>: Sized {
+	/// Splits off the first `length` units of the input, failing if there
+	/// are fewer of them.
+	fn take_units(self, length: usize) -> PResult<Self, Self, E>;
+}
+
+/// Runs `length_parser` and then takes as many units of the input as it
+/// returned.
+///
+/// The units are characters for `&str` and bytes for `&[u8]`.  If fewer of
+/// them remain, the end of input error of the input type is returned:
+/// [`string::Error::End`][crate::string::Error::End] or
+/// [`bytes::Error::End`][crate::bytes::Error::End].
+///
+/// ```rust
+/// use komb::{Parser, combinator::length_data, string::usize};
+///
+/// let p = length_data(usize.before(':'));
+///
+/// assert_eq!(Ok(("grüß", ",")), p.parse("4:grüß,"));
+/// assert_eq!(Ok(("", "x")), p.parse("0:x"));
+/// assert!(p.parse("5:grüß").is_err());
+/// ```
+///
+/// Pascal-style strings are prefixed by their length in a single byte:
+///
+/// ```rust
+/// use komb::{Parser, bytes::{take, Error}, combinator::length_data};
+///
+/// let p = length_data(take(1).map_out(|b: &[u8]| usize::from(b[0])));
+///
+/// let input: &[u8] = b"\x03abcd";
+/// assert_eq!(Ok((&b"abc"[..], &b"d"[..])), p.parse(input));
+/// assert!(matches!(p.parse(b"\x05abc"), Err(Error::End { .. })));
+/// ```
+pub fn length_data<'a, I, E>(
+	length_parser: impl Parser<'a, I, usize, E>,
+) -> impl Parser<'a, I, I, E>
+where
+	I: TakeUnits<E> + Copy + 'a,
+	E: 'a,
+{
+	move |input| {
+		let (length, rest) = length_parser.parse(input)?;
+		rest.take_units(length)
+	}
+}
+
+/// Runs `parser` and checks its output with `predicate`.
+///
+/// If the predicate returns `false`, an error with the "verification failed"
+/// [message][ParseError::message] is returned and the input isn't consumed.
+///
+/// ```rust
+/// use komb::{Parser, combinator::verify, string::{u32, Error}};
+///
+/// let p = verify(u32, |n| *n < 256);
+///
+/// assert_eq!(Ok((255, "")), p.parse("255"));
+/// assert_eq!(
+///     Err(Error::Message { message: "verification failed", span: "" }),
+///     p.parse("300"),
+/// );
+/// ```
+pub fn verify<'a, I, O, E, F>(
+	parser: impl Parser<'a, I, O, E>,
+	predicate: F,
+) -> impl Parser<'a, I, O, E>
+where
+	I: Copy + 'a,
+	O: 'a,
+	E: ParseError<I> + 'a,
+	F: Fn(&O) -> bool + 'a,
+{
+	move |input| {
+		let (output, rest) = parser.parse(input)?;
+		if predicate(&output) {
+			Ok((output, rest))
+		} else {
+			Err(E::message(input, "verification failed"))
+		}
+	}
+}
+
+/// Counts how many times `parser` succeeds by incrementing `counter`.
+///
+/// Wrapping every branch of a [`choice`] shows how often each of them
+/// matches, which helps to order the branches so that the common cases are
+/// tried first.
+///
+/// ```rust
+/// use core::cell::Cell;
+/// use komb::{Parser, combinator::{choice, profile}};
+///
+/// let counters = [Cell::new(0), Cell::new(0), Cell::new(0)];
+/// let p = choice((
+///     profile(&counters[0], "a"),
+///     profile(&counters[1], "b"),
+///     profile(&counters[2], "c"),
+/// ))
+/// .skip_many();
+///
+/// p.parse("abbcbbb").unwrap();
+///
+/// assert_eq!(1, counters[0].get());
+/// assert_eq!(5, counters[1].get());
+/// assert_eq!(1, counters[2].get());
+/// ```
+pub fn profile<'a, I, O, E>(
+	counter: &'a Cell<usize>,
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, O, E> {
+	move |input| {
+		let result = parser.parse(input);
+		if result.is_ok() {
+			counter.set(counter.get() + 1);
+		}
+		result
+	}
+}
+
+/// Remembers the positions where `parser` failed and returns the same error
+/// without running it again when it is applied at one of them.
+///
+/// This speeds up grammars where several alternatives start with the same
+/// parser, which fails in the same spot again and again.  Successes aren't
+/// cached.
+///
+/// A position is identified by the address and the length of the remaining
+/// input, so different inputs never share the cached errors.  The cache is
+/// only correct if `parser` is pure, that is it always returns the same result
+/// on the same input.
+///
+/// ```rust
+/// use core::cell::Cell;
+/// use komb::{Parser, combinator::cache_failures, string::u32};
+///
+/// let runs = Cell::new(0);
+/// let number = cache_failures(|input| {
+///     runs.set(runs.get() + 1);
+///     u32.parse(input)
+/// });
+/// let input = "auto";
+///
+/// assert!(number.parse(input).is_err());
+/// assert!(number.parse(input).is_err());
+/// assert_eq!(1, runs.get());
+///
+/// // another input of the same length is parsed anew
+/// assert_eq!(Ok((1234, "")), number.parse("1234"));
+/// assert_eq!(2, runs.get());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn cache_failures<'a, I, O, E>(
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, O, E>
+where
+	I: Sliceable,
+	E: Clone,
+{
+	let failures = RefCell::new(BTreeMap::new());
+
+	move |input: I| {
+		let position = (input.as_ptr(), input.len());
+		if let Some(error) = failures.borrow().get(&position) {
+			return Err(E::clone(error));
+		}
+
+		let result = parser.parse(input);
+		if let Err(error) = &result {
+			failures.borrow_mut().insert(position, error.clone());
+		}
+		result
+	}
+}
@@ -0,0 +1,125 @@
+use crate::{PResult, ParseError, Parser};
+
+pub struct Permutation<T>(T);
+
+/// Matches all of the parsers exactly once, in any order, and returns their
+/// outputs in the order of declaration.
+///
+/// Every round tries the parsers which haven't matched yet against the rest of
+/// the input.  When a round makes no progress, the error of the first missing
+/// parser is returned.  An error marked by [`cut`][super::cut] is returned
+/// immediately.
+///
+/// ```rust
+/// use komb::Parser;
+/// use komb::combinator::{permutation, preceded};
+/// use komb::string::{u32, Error};
+///
+/// let width = preceded('w', u32);
+/// let height = preceded('h', u32);
+/// let depth = preceded('d', u32);
+/// let p = permutation((width, height, depth));
+///
+/// assert_eq!(Ok(((1, 2, 3), " rest")), p.parse("d3w1h2 rest"));
+///
+/// let error = p.parse("h2w1").unwrap_err();
+/// assert!(matches!(error, Error::End { .. }));
+///
+/// // `w` is missing
+/// assert_eq!(Err(Error::Unmatched { span: "x" }), p.parse("h2x"));
+/// ```
+pub fn permutation<'p, P: 'p, I, O, E>(parsers: P) -> impl Parser<'p, I, O, E>
+where
+	Permutation<P>: Parser<'p, I, O, E>,
+{
+	Permutation(parsers)
+}
+
+macro_rules! impl_permutation {
+	($($p:ident $o:ident $index:tt),*) => {
+
+	impl<'a, I, E, $($p, $o,)*> Parser<'a, I, ($($o,)*), E>
+		for Permutation<($($p,)*)>
+	where
+		I: Copy,
+		$($p: Parser<'a, I, $o, E>,)*
+		E: ParseError<I>,
+	{
+		fn parse(&self, input: I) -> PResult<I, ($($o,)*), E> {
+			let mut outputs = ($(None::<$o>,)*);
+			let mut rest = input;
+
+			loop {
+				let mut matched = false;
+				let mut error = None;
+
+				$(
+				if outputs.$index.is_none() {
+					match self.0.$index.parse(rest) {
+						Ok((o, r)) => {
+							outputs.$index = Some(o);
+							rest = r;
+							matched = true;
+						}
+						Err(err) if err.is_cut() => {
+							return Err(err)
+						}
+						Err(err) => {
+							error.get_or_insert(err);
+						}
+					}
+				}
+				)*
+
+				match error {
+					None => break,
+					Some(error) if !matched => return Err(error),
+					Some(_) => {}
+				}
+			}
+
+			Ok((($(outputs.$index.unwrap(),)*), rest))
+		}
+	}
+
+	};
+}
+
+impl_permutation!(P0 O0 0, P1 O1 1);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10, P11 O11 11);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10, P11 O11 11, P12 O12 12);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10, P11 O11 11, P12 O12 12, P13 O13 13);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10, P11 O11 11, P12 O12 12, P13 O13 13, P14 O14 14);
+impl_permutation!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7, P8 O8 8, P9 O9 9, P10 O10 10, P11 O11 11, P12 O12 12, P13 O13 13, P14 O14 14, P15 O15 15);
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn any_order() {
+		let parser = permutation(("a", "b", "c"));
+		assert_eq!(Ok((("a", "b", "c"), "")), parser.parse("cab"));
+		assert_eq!(Ok((("a", "b", "c"), "a")), parser.parse("bcaa"));
+	}
+
+	#[test]
+	fn first_missing() {
+		use crate::string::Error;
+
+		let parser = permutation(("a", "b", "c"));
+		assert_eq!(
+			Err(Error::Unmatched { span: "x" }),
+			parser.parse("bx")
+		);
+	}
+}
@@ -0,0 +1,168 @@
+//! Parser for shell glob patterns.
+//!
+//! [`pattern`] splits a pattern like `src/*.rs` into [`GlobToken`]s, which
+//! can be compiled into a matcher.
+//!
+//! ```rust
+//! use komb::{Parser, glob::{pattern, GlobToken}};
+//!
+//! let (tokens, _) = pattern.parse("*.rs").unwrap();
+//! assert_eq!(vec![GlobToken::Star, GlobToken::Literal(".rs")], tokens);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{
+	combinator::{choice, many0, optional, preceded},
+	string::{all_consuming, any_char, none_of, none_of_char, Error},
+	PResult, ParseError, Parser,
+};
+
+/// A part of a glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobToken<'a> {
+	/// Text which has to match exactly.  An escaped metacharacter, like
+	/// `\*`, is a literal of its own.
+	Literal(&'a str),
+	/// `*`, which matches any string, including an empty one.
+	Star,
+	/// `?`, which matches any single character.
+	Question,
+	/// A bracket expression like `[a-z_]`, which matches a single
+	/// character.
+	CharClass {
+		/// `true` if the class starts with `!` or `^` and matches the
+		/// characters outside of the `ranges`.
+		negated: bool,
+		/// Inclusive character ranges.  A single character `c` is stored
+		/// as `(c, c)`.
+		ranges: Vec<(char, char)>,
+	},
+}
+
+/// Parses a whole glob pattern.
+///
+/// Any character can be escaped with a backslash, both inside and outside
+/// of a bracket expression.  A `]` right after the opening bracket (and the
+/// optional negation) is a member of the class, and so is a `-` at its end.
+/// Unclosed brackets and reversed ranges, like `[z-a]`, are rejected.
+///
+/// ```rust
+/// use komb::{Parser, glob::{pattern, GlobToken}};
+///
+/// assert_eq!(
+///     Ok((
+///         vec![
+///             GlobToken::Literal("file"),
+///             GlobToken::Question,
+///             GlobToken::Literal(".txt"),
+///         ],
+///         "",
+///     )),
+///     pattern.parse("file?.txt"),
+/// );
+/// assert!(pattern.parse("[abc").is_err());
+/// ```
+pub fn pattern(input: &str) -> PResult<&str, Vec<GlobToken<'_>>, Error<'_>> {
+	all_consuming(many0(token)).parse(input)
+}
+
+/// Parses a single token of a glob pattern.
+pub fn token(input: &str) -> PResult<&str, GlobToken<'_>, Error<'_>> {
+	choice((
+		'*'.value(GlobToken::Star),
+		'?'.value(GlobToken::Question),
+		char_class,
+		preceded('\\', any_char).map_out(GlobToken::Literal),
+		none_of(&['*', '?', '[', '\\']).map_out(GlobToken::Literal),
+	))
+	.parse(input)
+}
+
+/// Parses a bracket expression.
+fn char_class(input: &str) -> PResult<&str, GlobToken<'_>, Error<'_>> {
+	let (_, rest) = '['.parse(input)?;
+	let (negated, rest) = optional(choice(('!', '^'))).parse(rest)?;
+	let (bracket, rest) = optional(']').parse(rest)?;
+	let (mut ranges, rest) = many0(class_member).parse(rest)?;
+	let (_, rest) = ']'.parse(rest)?;
+
+	if bracket.is_some() {
+		ranges.insert(0, (']', ']'));
+	}
+
+	let negated = negated.is_some();
+	Ok((GlobToken::CharClass { negated, ranges }, rest))
+}
+
+/// Parses a character or a range of characters in a bracket expression.
+fn class_member(input: &str) -> PResult<&str, (char, char), Error<'_>> {
+	let (start, rest) = class_char(input)?;
+
+	match preceded('-', class_char).parse(rest) {
+		Ok((end, _)) if start > end => {
+			Err(Error::message(input, "reversed character range"))
+		}
+		Ok((end, rest)) => Ok(((start, end), rest)),
+		Err(_) => Ok(((start, start), rest)),
+	}
+}
+
+/// Parses a possibly escaped character in a bracket expression.
+fn class_char(input: &str) -> PResult<&str, char, Error<'_>> {
+	choice((preceded('\\', any_char), none_of_char(&[']'])))
+		.map_out(|ch: &str| ch.chars().next().unwrap())
+		.parse(input)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use alloc::vec;
+
+	#[test]
+	fn negated_class() {
+		let (tokens, _) = pattern.parse("[!abc]*").unwrap();
+		let class = GlobToken::CharClass {
+			negated: true,
+			ranges: vec![('a', 'a'), ('b', 'b'), ('c', 'c')],
+		};
+		assert_eq!(vec![class, GlobToken::Star], tokens);
+	}
+
+	#[test]
+	fn ranges() {
+		let (tokens, _) = pattern.parse("[]a-z_-]").unwrap();
+		let class = GlobToken::CharClass {
+			negated: false,
+			ranges: vec![
+				(']', ']'),
+				('a', 'z'),
+				('_', '_'),
+				('-', '-'),
+			],
+		};
+		assert_eq!(vec![class], tokens);
+
+		assert!(pattern.parse("[z-a]").is_err());
+		assert!(pattern.parse("[]").is_err());
+	}
+
+	#[test]
+	fn escapes() {
+		let (tokens, _) = pattern.parse(r"a\*b[\]]").unwrap();
+		let class = GlobToken::CharClass {
+			negated: false,
+			ranges: vec![(']', ']')],
+		};
+		assert_eq!(
+			vec![
+				GlobToken::Literal("a"),
+				GlobToken::Literal("*"),
+				GlobToken::Literal("b"),
+				class,
+			],
+			tokens
+		);
+	}
+}
@@ -0,0 +1,165 @@
+//! Parsers for the head of an HTTP/1.1 request: the request line and the
+//! header fields.
+//!
+//! The body isn't parsed: [`request`] stops after the empty line which ends
+//! the headers and returns the rest of the input.  All of the parsed parts
+//! borrow from the input.
+//!
+//! This module requires the `http` feature.
+
+use alloc::vec::Vec;
+
+use crate::{
+	combinator::{many0, terminated},
+	string::{consume, digits, one_of, or0, take_until, take_while, Error},
+	PResult, Parser,
+};
+
+/// The head of an HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request<'a> {
+	/// The request method, like `GET`.
+	pub method: &'a str,
+	/// The request target, usually a path.
+	pub target: &'a str,
+	/// The protocol version, like `HTTP/1.1`.
+	pub version: &'a str,
+	/// The header fields in the order they appear.
+	pub headers: Vec<Header<'a>>,
+}
+
+/// A header field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header<'a> {
+	/// The field name.  It is case-insensitive, but returned as is.
+	pub name: &'a str,
+	/// The field value without the surrounding whitespace.
+	///
+	/// A value folded over several lines (the obsolete line folding) is
+	/// kept with the line breaks, see [`Header::unfolded`].
+	pub value: &'a str,
+}
+
+impl<'a> Header<'a> {
+	/// Returns the lines of a folded value without the surrounding
+	/// whitespace.  A value which isn't folded is a single line.
+	///
+	/// ```rust
+	/// use komb::http::Header;
+	///
+	/// let header = Header { name: "X-Long", value: "a\r\n  b" };
+	/// assert_eq!(vec!["a", "b"], header.unfolded().collect::<Vec<_>>());
+	/// ```
+	pub fn unfolded(&self) -> impl Iterator<Item = &'a str> {
+		self.value.split("\r\n").map(str::trim)
+	}
+}
+
+fn is_token_char(ch: char) -> bool {
+	ch.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(ch)
+}
+
+/// Parses a request line, like `GET /index.html HTTP/1.1\r\n`, and returns
+/// the method, the target and the version.
+pub fn request_line(
+	input: &str,
+) -> PResult<&str, (&str, &str, &str), Error<'_>> {
+	let method = take_while(is_token_char);
+	let target = take_until(|ch| ch == ' ' || ch.is_ascii_control());
+	let version = consume(("HTTP/", digits::<10>, '.', digits::<10>));
+
+	(method, ' ', target, ' ', version, "\r\n")
+		.map_out(|(method, _, target, _, version, _)| {
+			(method, target, version)
+		})
+		.parse(input)
+}
+
+/// Parses a header field line, including the continuation lines of a
+/// folded value.
+pub fn header(input: &str) -> PResult<&str, Header<'_>, Error<'_>> {
+	let blank = || or0(one_of(&[' ', '\t']));
+	let text = || or0(take_until(|ch| ch == '\r' || ch == '\n'));
+	let value = consume((
+		text(),
+		many0(("\r\n", one_of(&[' ', '\t']), text())),
+	));
+
+	let (name, rest) =
+		terminated(take_while(is_token_char), ':').parse(input)?;
+	let (_, rest) = blank().parse(rest)?;
+	let (value, rest) = terminated(value, "\r\n").parse(rest)?;
+
+	let value = value.trim_end();
+	Ok((Header { name, value }, rest))
+}
+
+/// Parses a request line and the header fields up to and including the
+/// empty line which ends them.
+///
+/// ```rust
+/// use komb::{Parser, http::{request, Header}};
+///
+/// let input = "GET /index.html HTTP/1.1\r\n\
+///              Host: example.com\r\n\
+///              Accept: text/html\r\n\
+///              \r\n\
+///              body";
+/// let (head, rest) = request.parse(input).unwrap();
+///
+/// assert_eq!("body", rest);
+/// assert_eq!(("GET", "/index.html", "HTTP/1.1"), (head.method, head.target, head.version));
+/// assert_eq!(
+///     vec![
+///         Header { name: "Host", value: "example.com" },
+///         Header { name: "Accept", value: "text/html" },
+///     ],
+///     head.headers,
+/// );
+///
+/// assert!(request.parse("GET /index.html\r\n\r\n").is_err());
+/// ```
+pub fn request(input: &str) -> PResult<&str, Request<'_>, Error<'_>> {
+	let ((method, target, version), rest) = request_line(input)?;
+	let (headers, rest) = terminated(many0(header), "\r\n").parse(rest)?;
+
+	let request = Request {
+		method,
+		target,
+		version,
+		headers,
+	};
+	Ok((request, rest))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn folded_header() {
+		let input = "X-Long: first \r\n\tsecond\r\nHost: a\r\n";
+		let (long, rest) = header(input).unwrap();
+
+		assert_eq!("X-Long", long.name);
+		assert_eq!("first \r\n\tsecond", long.value);
+		assert_eq!(
+			Ok((
+				Header {
+					name: "Host",
+					value: "a"
+				},
+				""
+			)),
+			header(rest)
+		);
+	}
+
+	#[test]
+	fn malformed_request_line() {
+		assert!(request_line("GET  /path HTTP/1.1\r\n").is_err());
+		assert!(request_line("GET /path HTTP/1\r\n").is_err());
+		assert!(request_line("GET /path HTTP/1.1\n").is_err());
+		assert!(request_line("/path HTTP/1.1\r\n").is_err());
+	}
+}
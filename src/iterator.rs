@@ -0,0 +1,130 @@
+//! Lazy iteration over the outputs of a repeatedly applied parser.
+//!
+//! Unlike [`many0`][crate::combinator::many0], [`iterator`] doesn't collect
+//! the outputs, so a long input can be processed in constant memory.
+//!
+//! ```rust
+//! use komb::{Parser, iterator::iterator, string::{line, u32}};
+//!
+//! let mut records = iterator("1\n2\n3\nx\n", u32.before(line));
+//! let sum: u32 = records.by_ref().sum();
+//!
+//! assert_eq!(6, sum);
+//! assert_eq!("x\n", records.rest());
+//! assert!(records.error().is_some());
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{progress, ParseError, Parser, Sliceable};
+
+/// An iterator which applies a parser to the rest of the input, see
+/// [`iterator`].
+pub struct ParseIterator<I, O, E, P> {
+	parser: P,
+	rest: I,
+	error: Option<E>,
+	done: bool,
+	output: PhantomData<fn() -> O>,
+}
+
+/// Returns an iterator which applies `parser` to `input` and then to the rest
+/// of the input after every match, and yields the outputs.
+///
+/// The iteration ends when `parser` fails, and the error is kept, see
+/// [`ParseIterator::error`].  A match which consumes no input counts as a
+/// failure, see [progress][crate::Sliceable#progress].
+pub fn iterator<'a, I, O, E, P>(
+	input: I,
+	parser: P,
+) -> ParseIterator<I, O, E, P>
+where
+	I: Sliceable,
+	P: Parser<'a, I, O, E>,
+	E: ParseError<I>,
+{
+	ParseIterator {
+		parser,
+		rest: input,
+		error: None,
+		done: false,
+		output: PhantomData,
+	}
+}
+
+impl<I, O, E, P> ParseIterator<I, O, E, P>
+where
+	I: Sliceable,
+{
+	/// Returns the input which hasn't been consumed yet.
+	pub fn rest(&self) -> I {
+		self.rest
+	}
+
+	/// Returns the error which ended the iteration, if any.
+	pub fn error(&self) -> Option<&E> {
+		self.error.as_ref()
+	}
+
+	/// Consumes the iterator and returns the unconsumed input and the error
+	/// which ended the iteration.
+	pub fn finish(self) -> (I, Option<E>) {
+		(self.rest, self.error)
+	}
+}
+
+impl<'a, I, O, E, P> Iterator for ParseIterator<I, O, E, P>
+where
+	I: Sliceable,
+	P: Parser<'a, I, O, E>,
+	E: ParseError<I>,
+{
+	type Item = O;
+
+	fn next(&mut self) -> Option<O> {
+		if self.done {
+			return None;
+		}
+
+		match progress(self.rest, self.parser.parse(self.rest)) {
+			Ok((out, rest)) => {
+				self.rest = rest;
+				Some(out)
+			}
+			Err(error) => {
+				self.error = Some(error);
+				self.done = true;
+				None
+			}
+		}
+	}
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+	use super::*;
+	use crate::string::{alphabetic, or0, Error};
+	use alloc::{vec, vec::Vec};
+
+	#[test]
+	fn stops_without_progress() {
+		let mut words =
+			iterator("ab cd", or0(alphabetic).before(or0(" ")));
+
+		assert_eq!(
+			vec!["ab", "cd"],
+			words.by_ref().collect::<Vec<_>>()
+		);
+		assert_eq!(None, words.next());
+		assert_eq!(
+			(
+				"",
+				Some(Error::Message {
+					message: "parser made no progress",
+					span: "",
+				})
+			),
+			words.finish()
+		);
+	}
+}
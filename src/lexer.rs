@@ -0,0 +1,195 @@
+//! A simple lexer which splits the source into tokens.
+//!
+//! A [`Lexer`] is built from a list of rules, each pairing a token kind with a
+//! parser.  It skips the trivia, like whitespace, before every token and picks
+//! the rule with the longest match.
+//!
+//! ```rust
+//! use komb::{Parser, lexer::Lexer, span::Span, string::{digits, whitespace}};
+//!
+//! let lexer = Lexer::new(vec![
+//!     ("number", digits::<10>.boxed()),
+//!     ("plus", '+'.boxed()),
+//! ])
+//! .trivia(whitespace);
+//!
+//! let tokens = lexer.tokenize("1 + 23").unwrap();
+//! assert_eq!(
+//!     vec![
+//!         ("number", "1", Span::new(0, 1)),
+//!         ("plus", "+", Span::new(2, 3)),
+//!         ("number", "23", Span::new(4, 6)),
+//!     ],
+//!     tokens,
+//! );
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{span::Span, string::Error, BoxedParser, Parser, Sliceable};
+
+/// A token: its kind, the matched text and its location in the source.
+pub type Token<'a, K> = (K, &'a str, Span);
+
+/// Splits the source into tokens, see the [module](self) documentation.
+pub struct Lexer<'a, K> {
+	rules: Vec<(K, BoxedParser<'a, &'a str, &'a str, Error<'a>>)>,
+	trivia: Option<BoxedParser<'a, &'a str, &'a str, Error<'a>>>,
+}
+
+impl<'a, K> Lexer<'a, K>
+where
+	K: Copy,
+{
+	/// Creates a lexer from rules, which are tried in order at every
+	/// position.
+	///
+	/// The rule with the longest match wins, and of the rules with matches
+	/// of the same length, the first one does.  Matches which consume
+	/// nothing are ignored.
+	pub fn new(
+		rules: Vec<(K, BoxedParser<'a, &'a str, &'a str, Error<'a>>)>,
+	) -> Self {
+		Lexer {
+			rules,
+			trivia: None,
+		}
+	}
+
+	/// Sets the parser for the trivia, like whitespace or comments, which
+	/// is skipped before every token and at the end of the source.
+	pub fn trivia(
+		mut self,
+		trivia: impl Parser<'a, &'a str, &'a str, Error<'a>> + 'a,
+	) -> Self {
+		self.trivia = Some(trivia.boxed());
+		self
+	}
+
+	/// Splits the whole `source` into tokens.
+	///
+	/// If no rule matches at some position, returns [`Error::Unmatched`]
+	/// pointing to the character there.
+	pub fn tokenize(
+		&self,
+		source: &'a str,
+	) -> Result<Vec<Token<'a, K>>, Error<'a>> {
+		let mut tokens = Vec::new();
+		let mut rest = self.skip_trivia(source);
+
+		while let Some(ch) = rest.chars().next() {
+			let (kind, text) = self.longest_match(rest).ok_or(
+				Error::Unmatched {
+					span: &rest[..ch.len_utf8()],
+				},
+			)?;
+
+			let start = source.consumed(rest).len();
+			tokens.push((
+				kind,
+				text,
+				Span::new(start, start + text.len()),
+			));
+			rest = self.skip_trivia(&rest[text.len()..]);
+		}
+
+		Ok(tokens)
+	}
+
+	fn skip_trivia(&self, mut input: &'a str) -> &'a str {
+		let Some(trivia) = &self.trivia else {
+			return input;
+		};
+
+		while let Ok((_, rest)) = trivia.parse(input) {
+			if rest.len() == input.len() {
+				break;
+			}
+			input = rest;
+		}
+
+		input
+	}
+
+	fn longest_match(&self, input: &'a str) -> Option<(K, &'a str)> {
+		let mut longest: Option<(K, &'a str)> = None;
+
+		for (kind, rule) in &self.rules {
+			let Ok((_, rest)) = rule.parse(input) else {
+				continue;
+			};
+			let text = input.consumed(rest);
+
+			if text.len()
+				> longest.map_or(0, |(_, text)| text.len())
+			{
+				longest = Some((*kind, text));
+			}
+		}
+
+		longest
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::string::{digits, ident_ascii, whitespace};
+	use alloc::vec;
+
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	enum Kind {
+		Ident,
+		Number,
+		Assign,
+		Equals,
+		Plus,
+	}
+
+	fn lexer<'a>() -> Lexer<'a, Kind> {
+		Lexer::new(vec![
+			(Kind::Ident, ident_ascii.boxed()),
+			(Kind::Number, digits::<10>.boxed()),
+			(Kind::Assign, '='.boxed()),
+			(Kind::Equals, "==".boxed()),
+			(Kind::Plus, '+'.boxed()),
+		])
+		.trivia(whitespace)
+	}
+
+	#[test]
+	fn tokens_with_spans() {
+		assert_eq!(
+			Ok(vec![
+				(Kind::Ident, "x", Span::new(0, 1)),
+				(Kind::Assign, "=", Span::new(2, 3)),
+				(Kind::Number, "42", Span::new(4, 6)),
+				(Kind::Plus, "+", Span::new(7, 8)),
+				(Kind::Ident, "y", Span::new(9, 10)),
+			]),
+			lexer().tokenize("x = 42 + y "),
+		);
+	}
+
+	#[test]
+	fn longest_match() {
+		let tokens = lexer().tokenize("a==b").unwrap();
+		let kinds: Vec<_> =
+			tokens.iter().map(|(kind, ..)| *kind).collect();
+
+		assert_eq!(vec![Kind::Ident, Kind::Equals, Kind::Ident], kinds);
+	}
+
+	#[test]
+	fn unmatched() {
+		let source = "x = 4 * 2";
+
+		assert_eq!(
+			Err(Error::Unmatched {
+				span: &source[6..7]
+			}),
+			lexer().tokenize(source),
+		);
+		assert_eq!(Ok(vec![]), lexer().tokenize("  "));
+	}
+}
@@ -2,7 +2,22 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{rc::Rc, vec::Vec};
+
+pub mod bytes;
 pub mod combinator;
+#[cfg(feature = "alloc")]
+pub mod glob;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod iterator;
+#[cfg(feature = "alloc")]
+pub mod lexer;
+pub mod span;
 pub mod string;
 
 /// The result type returned by parsers.
@@ -13,6 +28,179 @@ pub mod string;
 /// and `rest` is a sub-slice of input with the parsed part cut off.
 pub type PResult<I, O, E> = Result<(O, I), E>;
 
+/// Input types which are sub-slices of the original input.
+///
+/// Parsers only ever cut a prefix off the input, so comparing the lengths of
+/// the input and the rest is enough to tell how much of it was consumed.
+///
+/// # Progress
+///
+/// Combinators which apply a parser in a loop rely on this to detect a match
+/// which consumes no input, since the parser would match the same way
+/// forever.  All of them treat such a match as a failure of the parser with
+/// the message "parser made no progress", and its output is discarded.  The
+/// combinator then does what it does on any other failure: repetitions like
+/// [`many0`][combinator::many0] or [`Parser::skip_many`] stop there, while
+/// [`many1`][combinator::many1] before its first match,
+/// [`many_till`][combinator::many_till] or [`Parser::repeat_until`] return
+/// the error.
+pub trait Sliceable: Copy {
+	/// Returns the length of the input: bytes for `str` and elements for
+	/// slices.
+	fn len(&self) -> usize;
+
+	/// Returns `true` if there is no input left.
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns a pointer to the start of the input.
+	///
+	/// Together with the [length][Self::len] it identifies a position in a
+	/// particular input, see [`cache_failures`][combinator::cache_failures].
+	fn as_ptr(&self) -> *const ();
+
+	/// Returns the prefix of `self` which ends where `rest` starts, that is
+	/// the part of the input a parser consumed if it returned `rest`.
+	///
+	/// # Panics
+	///
+	/// Panics if `rest` doesn't start inside of `self` (or right after
+	/// it).
+	///
+	/// ```rust
+	/// use komb::Sliceable;
+	///
+	/// let input = "abc";
+	/// assert_eq!("ab", input.consumed(&input[2..]));
+	///
+	/// let input: &[u8] = &[1, 2, 3];
+	/// assert_eq!(&[1], input.consumed(&input[1..]));
+	/// ```
+	fn consumed(self, rest: Self) -> Self;
+}
+
+impl Sliceable for &str {
+	fn len(&self) -> usize {
+		str::len(self)
+	}
+
+	fn as_ptr(&self) -> *const () {
+		str::as_ptr(self).cast()
+	}
+
+	fn consumed(self, rest: Self) -> Self {
+		let start = self.as_ptr() as usize;
+		let end = rest.as_ptr() as usize;
+
+		assert!(start <= end);
+		let length = end - start;
+		assert!(length <= self.len());
+
+		&self[..length]
+	}
+}
+
+impl<T> Sliceable for &[T] {
+	fn len(&self) -> usize {
+		<[T]>::len(self)
+	}
+
+	fn as_ptr(&self) -> *const () {
+		<[T]>::as_ptr(self).cast()
+	}
+
+	fn consumed(self, rest: Self) -> Self {
+		let size = core::mem::size_of::<T>();
+		// zero-sized elements all have the same address
+		if size == 0 {
+			return &self[..self.len() - rest.len()];
+		}
+
+		let start = self.as_ptr() as usize;
+		let end = rest.as_ptr() as usize;
+
+		assert!(start <= end);
+		let length = (end - start) / size;
+		assert!(length <= self.len());
+
+		&self[..length]
+	}
+}
+
+/// Turns a match which consumed nothing of `input` into an error, see
+/// [progress](Sliceable#progress).
+fn progress<I, O, E>(input: I, result: PResult<I, O, E>) -> PResult<I, O, E>
+where
+	I: Sliceable,
+	E: ParseError<I>,
+{
+	match result {
+		Ok((_, rest)) if rest.len() == input.len() => {
+			Err(E::message(input, "parser made no progress"))
+		}
+		result => result,
+	}
+}
+
+/// Errors which can be created by the type-agnostic combinators.
+///
+/// Most combinators only pass through the errors of the parsers they wrap,
+/// but some, like [`verify`][combinator::verify], have to fail on their own.
+/// These require the error type to implement this trait.
+pub trait ParseError<I> {
+	/// Creates an error with a `message` which describes a failure at the
+	/// start of `input`.
+	fn message(input: I, message: &'static str) -> Self;
+
+	/// Marks the error as fatal, see [`cut`][combinator::cut].
+	///
+	/// By default errors can't be marked and this returns the error
+	/// unchanged.
+	fn cut(self) -> Self
+	where
+		Self: Sized,
+	{
+		self
+	}
+
+	/// Returns `true` if the error was marked by [`cut`][Self::cut].
+	/// Alternatives aren't tried after such an error.
+	fn is_cut(&self) -> bool {
+		false
+	}
+
+	/// Records that the error was returned by a parser named `label`, see
+	/// [`stacked`][combinator::stacked].
+	///
+	/// By default the error is returned unchanged.
+	fn context(self, label: &'static str) -> Self
+	where
+		Self: Sized,
+	{
+		let _ = label;
+		self
+	}
+
+	/// Combines the errors of [`choice`][combinator::choice] when all of
+	/// its alternatives failed on `input`.  `expected` yields the
+	/// [literal][Parser::expected] of each alternative, and `last` is the
+	/// error of the last one.
+	///
+	/// By default `last` is returned.
+	fn one_of(
+		input: I,
+		expected: &mut dyn Iterator<Item = Option<&str>>,
+		last: Self,
+	) -> Self
+	where
+		Self: Sized,
+	{
+		let _ = (input, expected);
+		last
+	}
+}
+
 /// The core trait which defines parsers.
 ///
 /// This trait is automatically [implemented for functions][impl] which take a
@@ -49,8 +237,8 @@ pub type PResult<I, O, E> = Result<(O, I), E>;
 /// ```
 ///
 /// Most custom parsers can be created using the provided ones.  Since these
-/// return unnamed closures, `-> impl Parse<'a, I, O, E>` has to be used as the
-/// return type.
+/// return unnamed closures, `-> impl Parser<'a, I, O, E>` has to be used as
+/// the return type.
 ///
 /// ```rust
 /// use komb::Parser;
@@ -84,7 +272,7 @@ pub type PResult<I, O, E> = Result<(O, I), E>;
 /// Finally, the `Parser` trait can be implemented manually to allow for more
 /// complex behavior.  In this case only the `parse` method needs to be
 /// implemented.  The implementation mustn't overwrite any of the [provided
-/// methods](#provided-methods).
+/// methods](#provided-methods), except for [`expected`][Parser::expected].
 ///
 /// [impl]: #impl-Parser<'a,+I,+O,+E>-for-F
 pub trait Parser<'a, I, O, E> {
@@ -94,14 +282,50 @@ pub trait Parser<'a, I, O, E> {
 	/// pointers on implementing it.
 	fn parse(&self, input: I) -> PResult<I, O, E>;
 
+	/// Returns the literal which the parser matches, if it only matches
+	/// one.  [`choice`][combinator::choice] passes these to
+	/// [`ParseError::one_of`] to list what its alternatives expected.
+	///
+	/// By default this returns `None`.
+	fn expected(&self) -> Option<&str> {
+		None
+	}
+
 	/// Creates a copy of the parser.
-	fn clone(&self) -> impl Parser<I, O, E>
+	fn clone(&self) -> impl Parser<'_, I, O, E>
 	where
 		Self: Sized,
 	{
 		move |input| self.parse(input)
 	}
 
+	/// Moves the parser into a reference-counted [`BoxedParser`], which
+	/// can be cloned and stored by value.
+	///
+	/// Unlike [`clone`][Parser::clone], the copies don't borrow the
+	/// original parser, so they can be kept in a struct or used in several
+	/// branches of a grammar.
+	///
+	/// ```rust
+	/// use komb::{BoxedParser, Parser, string::{u32, Error}};
+	///
+	/// struct Grammar<'a> {
+	///     number: BoxedParser<'a, &'a str, u32, Error<'a>>,
+	/// }
+	///
+	/// let grammar = Grammar { number: u32.boxed() };
+	/// let pair = (grammar.number.clone(), ',', grammar.number.clone());
+	///
+	/// assert_eq!(Ok(((1, ",", 2), "")), pair.parse("1,2"));
+	/// ```
+	#[cfg(feature = "alloc")]
+	fn boxed(self) -> BoxedParser<'a, I, O, E>
+	where
+		Self: Sized + 'a,
+	{
+		BoxedParser(Rc::new(self))
+	}
+
 	/// Converts the output type using the `Into` trait.
 	fn coerce<'s, OX>(self) -> impl Parser<'s, I, OX, E>
 	where
@@ -157,6 +381,34 @@ pub trait Parser<'a, I, O, E> {
 		move |input| self.parse(input).map(|(out, rest)| (f(out), rest))
 	}
 
+	/// Like [`map_out`][Parser::map_out], but `f` also receives the part of
+	/// the input which the parser consumed.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::u32};
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// struct Token<'a> {
+	///     value: u32,
+	///     lexeme: &'a str,
+	/// }
+	///
+	/// let p = u32.map_with_span(|value, lexeme| Token { value, lexeme });
+	///
+	/// assert_eq!(Ok((Token { value: 7, lexeme: "007" }, " ")), p.parse("007 "));
+	/// ```
+	fn map_with_span<'s, OX, F>(self, f: F) -> impl Parser<'s, I, OX, E>
+	where
+		Self: Sized + 's,
+		I: Sliceable,
+		F: Fn(O, I) -> OX + 's,
+	{
+		move |input: I| {
+			let (out, rest) = self.parse(input)?;
+			Ok((f(out, input.consumed(rest)), rest))
+		}
+	}
+
 	/// Applies a transformation to the error or does nothing if the parse
 	/// succeeds.
 	fn map_err<'s, F>(self, f: F) -> impl Parser<'s, I, O, E>
@@ -167,6 +419,91 @@ pub trait Parser<'a, I, O, E> {
 		move |input| self.parse(input).map_err(&f)
 	}
 
+	/// Replaces the error if it was caused by an error of type `T`.
+	///
+	/// On failure, the chain of [`source`][core::error::Error::source]
+	/// errors is searched for a `T`.  If one is found, the error is
+	/// replaced with the result of `f`, otherwise it is returned
+	/// unchanged.  This allows translating low-level errors, like
+	/// [`ParseIntError`][core::num::ParseIntError], into domain-specific
+	/// ones.
+	///
+	/// ```rust
+	/// use core::num::{IntErrorKind, ParseIntError};
+	/// use komb::{Parser, PResult, string};
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// enum MyError {
+	///     Int(ParseIntError),
+	///     TooLarge,
+	///     Other,
+	/// }
+	///
+	/// impl core::fmt::Display for MyError {
+	///     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+	///         match self {
+	///             MyError::Int(error) => error.fmt(f),
+	///             MyError::TooLarge => f.write_str("Byte value is too large"),
+	///             MyError::Other => f.write_str("Not a byte"),
+	///         }
+	///     }
+	/// }
+	///
+	/// impl core::error::Error for MyError {
+	///     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+	///         match self {
+	///             MyError::Int(error) => Some(error),
+	///             _ => None,
+	///         }
+	///     }
+	/// }
+	///
+	/// fn byte(input: &str) -> PResult<&str, u8, MyError> {
+	///     string::u8.parse(input).map_err(|error| match error {
+	///         string::Error::ParseInt { error, .. } => MyError::Int(error),
+	///         _ => MyError::Other,
+	///     })
+	/// }
+	///
+	/// let p = byte.map_err_downcast(|error: &ParseIntError| {
+	///     match error.kind() {
+	///         IntErrorKind::PosOverflow => MyError::TooLarge,
+	///         _ => MyError::Other,
+	///     }
+	/// });
+	///
+	/// assert_eq!(Ok((255, "")), p.parse("255"));
+	/// assert_eq!(Err(MyError::TooLarge), p.parse("300"));
+	/// assert_eq!(Err(MyError::Other), p.parse("x"));
+	/// ```
+	fn map_err_downcast<'s, T, F, EX>(
+		self,
+		f: F,
+	) -> impl Parser<'s, I, O, E>
+	where
+		Self: Sized + 's,
+		E: core::error::Error,
+		T: core::error::Error + 'static,
+		F: Fn(&T) -> EX + 's,
+		EX: Into<E>,
+	{
+		move |input| {
+			self.parse(input).map_err(|err| {
+				let mut source = err.source();
+				while let Some(error) = source {
+					if let Some(error) =
+						error.downcast_ref::<T>()
+					{
+						return f(error).into();
+					}
+					source = error.source();
+				}
+
+				err
+			})
+		}
+	}
+
 	/// Replace the output of a parser with `value`.
 	///
 	/// If the parser fails, the error remains unchanged.
@@ -180,6 +517,9 @@ pub trait Parser<'a, I, O, E> {
 
 	/// Calls the `other` parser if this one fails and returns it's result
 	/// instead.
+	///
+	/// If the error is [cut][combinator::cut], it is returned and `other`
+	/// isn't called.
 	fn or<'s>(
 		self,
 		other: impl Parser<'s, I, O, E>,
@@ -187,8 +527,134 @@ pub trait Parser<'a, I, O, E> {
 	where
 		Self: Sized + 's,
 		I: Copy,
+		E: ParseError<I>,
+	{
+		move |input| match self.parse(input) {
+			Err(err) if !err.is_cut() => other.parse(input),
+			result => result,
+		}
+	}
+
+	/// Returns `Some(output)` if the parser succeeds and `None` with
+	/// untouched input if it fails.
+	///
+	/// This is [`optional`][combinator::optional] in method form.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::digits};
+	///
+	/// let p = '-'.opt().before(digits::<10>);
+	///
+	/// assert_eq!(Ok((Some("-"), "")), p.parse("-12"));
+	/// assert_eq!(Ok((None, "")), p.parse("12"));
+	/// assert!(p.parse("-").is_err());
+	/// ```
+	fn opt<'s>(self) -> impl Parser<'s, I, Option<O>, E>
+	where
+		Self: Sized + 's,
+		I: Copy,
+		E: ParseError<I>,
 	{
-		move |input| self.parse(input).or_else(|_| other.parse(input))
+		move |input| match self.parse(input) {
+			Ok((out, rest)) => Ok((Some(out), rest)),
+			Err(err) if err.is_cut() => Err(err),
+			Err(_) => Ok((None, input)),
+		}
+	}
+
+	/// Like [`opt`][Parser::opt], but also returns whether the parser
+	/// matched.
+	///
+	/// This is clearer to branch on than the `Option` alone when the output
+	/// is an `Option` itself.
+	///
+	/// ```rust
+	/// use komb::{Parser, combinator::optional};
+	///
+	/// let p = optional('x').opt_flagged();
+	///
+	/// assert_eq!(Ok(((true, Some(Some("x"))), "")), p.parse("x"));
+	/// // `optional` never fails, so the flag is always set
+	/// assert_eq!(Ok(((true, Some(None)), "y")), p.parse("y"));
+	///
+	/// let p = 'x'.opt_flagged();
+	/// assert_eq!(Ok(((false, None), "y")), p.parse("y"));
+	/// ```
+	fn opt_flagged<'s>(self) -> impl Parser<'s, I, (bool, Option<O>), E>
+	where
+		Self: Sized + 's,
+		I: Copy,
+		E: ParseError<I>,
+	{
+		move |input| match self.parse(input) {
+			Ok((out, rest)) => Ok(((true, Some(out)), rest)),
+			Err(err) if err.is_cut() => Err(err),
+			Err(_) => Ok(((false, None), input)),
+		}
+	}
+
+	/// Returns the output together with whether the parser consumed any
+	/// input.
+	///
+	/// A parser which succeeds without consuming anything would match the
+	/// same way forever, so loops should stop on `false`.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::{alphabetic, or0}};
+	///
+	/// let p = or0(alphabetic).with_consumed_flag();
+	///
+	/// assert_eq!(Ok((("ab", true), "1")), p.parse("ab1"));
+	/// assert_eq!(Ok((("", false), "1")), p.parse("1"));
+	/// ```
+	fn with_consumed_flag<'s>(self) -> impl Parser<'s, I, (O, bool), E>
+	where
+		Self: Sized + 's,
+		I: Sliceable,
+	{
+		move |input: I| {
+			let (out, rest) = self.parse(input)?;
+			Ok(((out, rest.len() < input.len()), rest))
+		}
+	}
+
+	/// Fails with [`ParseError::message`] at the start of the input if the
+	/// output doesn't equal `expected`.
+	///
+	/// This is mostly useful to check grammars against fixtures.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::{u32, Error}};
+	///
+	/// let p = u32.assert_eq_out(42);
+	///
+	/// assert_eq!(Ok((42, "")), p.parse("42"));
+	/// assert_eq!(
+	///     Err(Error::Message {
+	///         message: "output doesn't equal the expected value",
+	///         span: "",
+	///     }),
+	///     p.parse("41"),
+	/// );
+	/// ```
+	fn assert_eq_out<'s>(self, expected: O) -> impl Parser<'s, I, O, E>
+	where
+		Self: Sized + 's,
+		I: Copy,
+		O: PartialEq + 's,
+		E: ParseError<I>,
+	{
+		move |input| {
+			let (out, rest) = self.parse(input)?;
+			if out != expected {
+				return Err(E::message(
+					input,
+					"output doesn't equal the expected value",
+				));
+			}
+
+			Ok((out, rest))
+		}
 	}
 
 	/// Replaces the error with `default` and untouched input if the parser
@@ -202,6 +668,226 @@ pub trait Parser<'a, I, O, E> {
 		move |input| self.parse(input).or(Ok((default.clone(), input)))
 	}
 
+	/// Never fails: returns the parser result as the output instead.
+	///
+	/// On success the output is `Ok(output)` and the input is consumed.  On
+	/// failure the output is `Err(error)` and the input is left untouched.
+	/// Unlike [`optional`][combinator::optional], the error is preserved,
+	/// so it can be reported later while the parsing continues.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::Error};
+	///
+	/// let p = "abc".soft();
+	///
+	/// assert_eq!(Ok((Ok("abc"), " rest")), p.parse("abc rest"));
+	/// assert_eq!(
+	///     Ok((Err(Error::Unmatched { span: "123" }), "123 rest")),
+	///     p.parse("123 rest"),
+	/// );
+	/// ```
+	fn soft<'s>(self) -> impl Parser<'s, I, Result<O, E>, E>
+	where
+		Self: Sized + 's,
+		I: Copy,
+	{
+		move |input| match self.parse(input) {
+			Ok((out, rest)) => Ok((Ok(out), rest)),
+			Err(err) => Ok((Err(err), input)),
+		}
+	}
+
+	/// Applies the parser until it fails, discarding all of the outputs.
+	///
+	/// This never fails and doesn't allocate, unlike collecting the
+	/// outputs with [`many0`][combinator::many0].  The repetition also
+	/// stops on a match which consumes no input, see
+	/// [progress](Sliceable#progress).
+	///
+	/// ```rust
+	/// use komb::{Parser, string::u32};
+	///
+	/// let p = '0'.skip_many().and_then(u32);
+	///
+	/// assert_eq!(Ok((42, "")), p.parse("00042"));
+	/// assert_eq!(Ok((7, "")), p.parse("7"));
+	/// ```
+	fn skip_many<'s>(self) -> impl Parser<'s, I, (), E>
+	where
+		Self: Sized + 's,
+		I: Sliceable,
+	{
+		move |input: I| {
+			let mut input = input;
+			while let Ok((_, rest)) = self.parse(input) {
+				if rest.len() == input.len() {
+					break;
+				}
+				input = rest;
+			}
+
+			Ok(((), input))
+		}
+	}
+
+	/// Applies the parser one or more times and returns the whole consumed
+	/// input as a single slice.
+	///
+	/// Since the matches are adjacent, they make up one contiguous slice,
+	/// so nothing is allocated, unlike with [`many`][Parser::many].  The
+	/// repetition stops when the parser fails or doesn't consume any input,
+	/// see [progress](Sliceable#progress).  If the first application fails,
+	/// its error is returned.
+	///
+	/// ```rust
+	/// use komb::{Parser, combinator::choice, string::{alphabetic, digits}};
+	///
+	/// let p = choice((alphabetic, digits::<10>)).join_spans();
+	///
+	/// assert_eq!(Ok(("ab12c3", " rest")), p.parse("ab12c3 rest"));
+	/// assert_eq!(Ok(("7", "-")), p.parse("7-"));
+	/// assert!(p.parse(" rest").is_err());
+	/// ```
+	fn join_spans<'s>(self) -> impl Parser<'s, I, I, E>
+	where
+		Self: Sized + 's,
+		I: Sliceable,
+	{
+		move |input: I| {
+			let (_, mut rest) = self.parse(input)?;
+			while let Ok((_, next)) = self.parse(rest) {
+				if next.len() == rest.len() {
+					break;
+				}
+				rest = next;
+			}
+
+			Ok((input.consumed(rest), rest))
+		}
+	}
+
+	/// Applies the parser zero or more times and collects the outputs into
+	/// a [`Vec`].
+	///
+	/// This is [`many0`][combinator::many0] in method form.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::alphabetic};
+	///
+	/// let p = alphabetic.before(' ').many();
+	///
+	/// assert_eq!(Ok((vec!["a", "b"], "1")), p.parse("a b 1"));
+	/// assert_eq!(Ok((vec![], "1")), p.parse("1"));
+	/// ```
+	#[cfg(feature = "alloc")]
+	fn many<'s>(self) -> impl Parser<'s, I, Vec<O>, E>
+	where
+		Self: Sized + 's,
+		I: Sliceable + 's,
+		O: 's,
+		E: ParseError<I> + 's,
+	{
+		combinator::many0(move |input| self.parse(input))
+	}
+
+	/// Parses zero or more outputs of this parser separated by `sep` and
+	/// collects them into a [`Vec`].
+	///
+	/// This is [`separated_list0`][combinator::separated_list0] in method
+	/// form.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::alphabetic};
+	///
+	/// let p = alphabetic.separated_by(",");
+	///
+	/// assert_eq!(Ok((vec!["a", "b", "c"], ";")), p.parse("a,b,c;"));
+	/// assert_eq!(Ok((vec![], ";")), p.parse(";"));
+	/// ```
+	#[cfg(feature = "alloc")]
+	fn separated_by<'s, OS>(
+		self,
+		sep: impl Parser<'s, I, OS, E> + 's,
+	) -> impl Parser<'s, I, Vec<O>, E>
+	where
+		Self: Sized + 's,
+		I: Sliceable + 's,
+		O: 's,
+		OS: 's,
+		E: ParseError<I> + 's,
+	{
+		combinator::separated_list0(move |input| self.parse(input), sep)
+	}
+
+	/// Applies the parser until `till` matches, passes each output to `f`
+	/// and returns the output of `till`.
+	///
+	/// This is the allocation-free version of
+	/// [`many_till`][combinator::many_till] and behaves the same way: `till`
+	/// is tried first on every iteration, an error of the parser is
+	/// returned, and so is the error for a match which consumes no input.
+	/// Since parsers are called by reference, `f` can't mutate its
+	/// environment directly, use a [`Cell`][core::cell::Cell] for that.
+	///
+	/// ```rust
+	/// use core::cell::Cell;
+	/// use komb::{Parser, string::u32};
+	///
+	/// let sum = Cell::new(0);
+	/// let p = u32.before(',').repeat_until(';', |n| sum.set(sum.get() + n));
+	///
+	/// assert_eq!(Ok((";", "")), p.parse("1,2,3,;"));
+	/// assert_eq!(6, sum.get());
+	/// assert!(p.parse("1,2").is_err());
+	/// ```
+	fn repeat_until<'s, OT, F>(
+		self,
+		till: impl Parser<'s, I, OT, E> + 's,
+		f: F,
+	) -> impl Parser<'s, I, OT, E>
+	where
+		Self: Sized + 's,
+		I: Sliceable,
+		E: ParseError<I>,
+		F: Fn(O) + 's,
+	{
+		move |mut input: I| loop {
+			if let Ok((end, rest)) = till.parse(input) {
+				return Ok((end, rest));
+			}
+
+			let (out, rest) = progress(input, self.parse(input))?;
+			f(out);
+			input = rest;
+		}
+	}
+
+	/// Parses `next` after `self` and returns both outputs as a pair.  If
+	/// either parser fails, the error is returned immediately.
+	///
+	/// ```rust
+	/// use komb::Parser;
+	///
+	/// let p = 'a'.and('b');
+	///
+	/// assert_eq!(Ok((("a", "b"), "")), p.parse("ab"));
+	/// assert!(p.parse("ac").is_err());
+	/// ```
+	fn and<'s, OX>(
+		self,
+		next: impl Parser<'s, I, OX, E>,
+	) -> impl Parser<'s, I, (O, OX), E>
+	where
+		Self: Sized + 's,
+		OX: 's,
+	{
+		move |input| {
+			let (out, rest) = self.parse(input)?;
+			let (next_out, rest) = next.parse(rest)?;
+			Ok(((out, next_out), rest))
+		}
+	}
+
 	/// If the parser succeeds, `and_then` discards the output and returns
 	/// the result of the `next` parser.  If either parser fails, the error
 	/// is returned immediately.
@@ -245,3 +931,41 @@ where
 		self(input)
 	}
 }
+
+/// A type-erased parser behind a reference-counted pointer, created by
+/// [`Parser::boxed`].
+///
+/// Cloning it only increments the reference count.
+#[cfg(feature = "alloc")]
+pub struct BoxedParser<'a, I, O, E>(Rc<dyn Parser<'a, I, O, E> + 'a>);
+
+#[cfg(feature = "alloc")]
+impl<I, O, E> BoxedParser<'_, I, O, E> {
+	/// Returns a new pointer to the same parser.
+	///
+	/// This inherent method takes precedence over both [`Clone::clone`] and
+	/// [`Parser::clone`], which would be ambiguous with both traits in
+	/// scope.
+	#[allow(clippy::should_implement_trait)]
+	pub fn clone(&self) -> Self {
+		BoxedParser(Rc::clone(&self.0))
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<I, O, E> Clone for BoxedParser<'_, I, O, E> {
+	fn clone(&self) -> Self {
+		BoxedParser::clone(self)
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, O, E> Parser<'a, I, O, E> for BoxedParser<'a, I, O, E> {
+	fn parse(&self, input: I) -> PResult<I, O, E> {
+		self.0.parse(input)
+	}
+
+	fn expected(&self) -> Option<&str> {
+		self.0.expected()
+	}
+}
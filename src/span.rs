@@ -0,0 +1,371 @@
+//! Source locations of parsed values.
+//!
+//! Parsers only see the part of the input which is left to parse, so the
+//! functions in this module take the whole `source` as an argument to compute
+//! offsets relative to it.
+
+use crate::{Parser, Sliceable};
+
+/// A byte range in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+	start: usize,
+	end: usize,
+}
+
+impl Span {
+	/// Creates a new span between `start` (inclusive) and `end`
+	/// (exclusive) byte offsets.
+	///
+	/// # Panics
+	///
+	/// Panics if `start` is greater than `end`.
+	pub fn new(start: usize, end: usize) -> Span {
+		assert!(start <= end);
+		Span { start, end }
+	}
+
+	/// Returns the location of `slice` in `source`, or `None` if `slice`
+	/// isn't a sub-slice of `source`.
+	///
+	/// ```rust
+	/// use komb::span::Span;
+	///
+	/// let source = "hello world";
+	///
+	/// assert_eq!(Some(Span::new(6, 11)), Span::of(source, &source[6..]));
+	/// assert_eq!(None, Span::of(source, "world"));
+	/// ```
+	pub fn of(source: &str, slice: &str) -> Option<Span> {
+		let start = (slice.as_ptr() as usize)
+			.checked_sub(source.as_ptr() as usize)?;
+		let end = start.checked_add(slice.len())?;
+
+		(end <= source.len()).then_some(Span { start, end })
+	}
+
+	/// Returns the part of `source` covered by the span.  This is the
+	/// inverse of [`Span::of`].
+	///
+	/// It can be used to attach a known location to an error after the
+	/// fact.
+	///
+	/// # Panics
+	///
+	/// Panics if the span is out of the bounds of `source` or doesn't lie
+	/// on `char` boundaries.
+	///
+	/// ```rust
+	/// use komb::{span::Span, string::Error};
+	///
+	/// let source = "let x = 1;";
+	/// let span = Span::new(4, 5);
+	/// let error = Error::Message { message: "unused variable", span: span.slice(source) };
+	///
+	/// assert_eq!("x", error.span());
+	/// assert_eq!(Some(span), error.span_in(source));
+	/// ```
+	pub fn slice(self, source: &str) -> &str {
+		&source[self.start..self.end]
+	}
+
+	/// The offset of the first byte of the span.
+	pub fn start(&self) -> usize {
+		self.start
+	}
+
+	/// The offset after the last byte of the span.
+	pub fn end(&self) -> usize {
+		self.end
+	}
+
+	/// The length of the span in bytes.
+	pub fn len(&self) -> usize {
+		self.end - self.start
+	}
+
+	/// Returns `true` if the span is zero-width.
+	pub fn is_empty(&self) -> bool {
+		self.start == self.end
+	}
+
+	/// Returns `true` if the byte at `offset` is inside the span.
+	pub fn contains(&self, offset: usize) -> bool {
+		self.start <= offset && offset < self.end
+	}
+
+	/// Returns the smallest span which covers both spans, including the
+	/// gap between them.
+	///
+	/// ```rust
+	/// use komb::span::Span;
+	///
+	/// assert_eq!(Span::new(2, 9), Span::new(2, 4).merge(Span::new(6, 9)));
+	/// assert_eq!(Span::new(2, 9), Span::new(6, 9).merge(Span::new(2, 4)));
+	/// ```
+	pub fn merge(self, other: Span) -> Span {
+		Span {
+			start: self.start.min(other.start),
+			end: self.end.max(other.end),
+		}
+	}
+
+	/// Returns the overlap of the spans, or `None` if they are disjoint.
+	///
+	/// Adjacent spans don't overlap.  A zero-width span intersects a span
+	/// which contains its offset.
+	///
+	/// ```rust
+	/// use komb::span::Span;
+	///
+	/// let span = Span::new(2, 6);
+	///
+	/// assert_eq!(Some(Span::new(4, 6)), span.intersect(Span::new(4, 9)));
+	/// assert_eq!(Some(Span::new(3, 3)), span.intersect(Span::new(3, 3)));
+	/// assert_eq!(None, span.intersect(Span::new(6, 9)));
+	/// assert_eq!(None, span.intersect(Span::new(0, 1)));
+	/// ```
+	pub fn intersect(self, other: Span) -> Option<Span> {
+		let start = self.start.max(other.start);
+		let end = self.end.min(other.end);
+
+		let point_inside = (self.is_empty() && other.contains(start))
+			|| (other.is_empty() && self.contains(start));
+		let overlaps = start < end || point_inside;
+		overlaps.then_some(Span { start, end })
+	}
+
+	/// Returns the 1-based line and column of the start of the span in
+	/// `source`.
+	///
+	/// Lines are separated by `\n`, so a `\r` before it is counted as the
+	/// last column of the line.  The column counts Unicode scalar values
+	/// (`char`s), not bytes, since the last newline.  A span which starts
+	/// right after a newline is at column 1 of the next line.
+	///
+	/// # Panics
+	///
+	/// Panics if the start of the span is outside of `source` or isn't on a
+	/// `char` boundary.
+	///
+	/// ```rust
+	/// use komb::span::Span;
+	///
+	/// let source = "ab\nжcd\n";
+	///
+	/// assert_eq!((1, 1), Span::new(0, 1).line_col(source));
+	/// assert_eq!((1, 3), Span::new(2, 3).line_col(source));
+	/// assert_eq!((2, 1), Span::new(3, 5).line_col(source));
+	/// assert_eq!((2, 2), Span::new(5, 6).line_col(source));
+	/// assert_eq!((3, 1), Span::new(8, 8).line_col(source));
+	/// ```
+	pub fn line_col(&self, source: &str) -> (usize, usize) {
+		let before = &source[..self.start];
+		let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+		let line = before.matches('\n').count() + 1;
+		let column = before[line_start..].chars().count() + 1;
+
+		(line, column)
+	}
+}
+
+/// A value together with the location it was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spanned<T> {
+	/// The parsed value.
+	pub node: T,
+	/// The part of the source `node` was parsed from.
+	pub span: Span,
+}
+
+/// Wraps the output of `parser` into [`Spanned`] with the location of the
+/// consumed input in `source`.
+///
+/// The offsets are in bytes for `str` and in elements for slices.
+///
+/// The parser must be called on sub-slices of `source`.  Since the spans are
+/// offsets into the same `source`, the wrapped parsers can be freely nested:
+/// every node of a tree built with [`map_out`][Parser::map_out] carries its
+/// own span.
+///
+/// # Panics
+///
+/// Panics if the input isn't a sub-slice of `source`.
+///
+/// ```rust
+/// use komb::{Parser, span::{Span, spanned_node}, string::alphabetic};
+///
+/// let source = "hello world";
+/// let p = " ".and_then(spanned_node(source, alphabetic));
+///
+/// let (word, _) = p.parse(&source[5..]).unwrap();
+/// assert_eq!("world", word.node);
+/// assert_eq!(Span::new(6, 11), word.span);
+/// ```
+pub fn spanned_node<'a, I, O, E>(
+	source: I,
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, Spanned<O>, E>
+where
+	I: Sliceable + 'a,
+{
+	move |input: I| {
+		let (node, rest) = parser.parse(input)?;
+		let start = source.consumed(input).len();
+		let end = source.consumed(rest).len();
+		let span = Span::new(start, end);
+
+		Ok((Spanned { node, span }, rest))
+	}
+}
+
+/// Like [`spanned_node`], but returns the output and its span as a tuple.
+///
+/// The span is made of offsets, so unlike the slice returned by
+/// [`with_recognized`][crate::combinator::with_recognized] it doesn't borrow
+/// the source and can be stored in an AST which outlives it.
+///
+/// # Panics
+///
+/// Panics if the input isn't a sub-slice of `source`.
+///
+/// ```rust
+/// use komb::{Parser, span::{Span, spanned}, string::u32};
+///
+/// let source = "x = 42";
+/// let p = "x = ".and_then(spanned(source, u32));
+///
+/// assert_eq!(Ok(((42, Span::new(4, 6)), "")), p.parse(source));
+/// ```
+pub fn spanned<'a, I, O, E>(
+	source: I,
+	parser: impl Parser<'a, I, O, E>,
+) -> impl Parser<'a, I, (O, Span), E>
+where
+	I: Sliceable + 'a,
+{
+	move |input: I| {
+		let (node, rest) = parser.parse(input)?;
+		let start = source.consumed(input).len();
+		let end = source.consumed(rest).len();
+
+		Ok(((node, Span::new(start, end)), rest))
+	}
+}
+
+/// Applies `parser` repeatedly, starting at `input`, and lazily yields the
+/// outputs with their spans in `base`.
+///
+/// The iteration stops at the first error or at a match which doesn't
+/// consume any input.  Nothing is collected, so it can be used to stream
+/// tokens.  Each span covers everything the parser consumed, including
+/// separators.
+///
+/// # Panics
+///
+/// The iterator panics if `input` isn't a sub-slice of `base`.
+///
+/// ```rust
+/// use komb::{Parser, span::{iter_spanned, Span}, string::alphabetic};
+///
+/// let base = "words: a,bb,ccc;";
+/// let mut words = iter_spanned(base, alphabetic.before(','), &base[7..]);
+///
+/// assert_eq!(Some(("a", Span::new(7, 9))), words.next());
+/// assert_eq!(Some(("bb", Span::new(9, 12))), words.next());
+/// assert_eq!(None, words.next());
+/// ```
+pub fn iter_spanned<'a, I, O, E>(
+	base: I,
+	parser: impl Parser<'a, I, O, E> + 'a,
+	input: I,
+) -> impl Iterator<Item = (O, Span)> + 'a
+where
+	I: Sliceable + 'a,
+	O: 'a,
+	E: 'a,
+{
+	let mut input = input;
+
+	core::iter::from_fn(move || {
+		let (out, rest) = parser.parse(input).ok()?;
+		if rest.len() == input.len() {
+			return None;
+		}
+
+		let start = base.consumed(input).len();
+		let end = base.consumed(rest).len();
+		input = rest;
+
+		Some((out, Span::new(start, end)))
+	})
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+	use super::*;
+	use crate::combinator::{delimited, optional};
+	use crate::string::{alphabetic, u32, whitespace};
+	use alloc::{vec, vec::Vec};
+
+	#[derive(Debug, PartialEq)]
+	struct Pair {
+		left: Spanned<u32>,
+		right: Spanned<u32>,
+	}
+
+	#[test]
+	fn nested() {
+		let source = "  (1, 23)";
+		let number = |input| spanned_node(source, u32).parse(input);
+		let pair = delimited(
+			"(",
+			(number, ", ", number).map_out(|(left, _, right)| {
+				Pair { left, right }
+			}),
+			")",
+		);
+		let p = whitespace.and_then(spanned_node(source, pair));
+
+		let (tree, rest) = p.parse(source).unwrap();
+		assert_eq!("", rest);
+		assert_eq!(Span::new(2, 9), tree.span);
+		assert_eq!(1, tree.node.left.node);
+		assert_eq!(Span::new(3, 4), tree.node.left.span);
+		assert_eq!(23, tree.node.right.node);
+		assert_eq!(Span::new(6, 8), tree.node.right.span);
+	}
+
+	#[test]
+	fn bytes() {
+		fn byte(input: &[u8]) -> crate::PResult<&[u8], u8, ()> {
+			input.split_first()
+				.map(|(b, rest)| (*b, rest))
+				.ok_or(())
+		}
+
+		let source: &[u8] = &[1, 2, 3, 4];
+		let p = byte.and_then(spanned_node(source, (byte, byte)));
+
+		let (pair, rest) = p.parse(source).unwrap();
+		assert_eq!((2, 3), pair.node);
+		assert_eq!(Span::new(1, 3), pair.span);
+		assert_eq!(&[4], rest);
+	}
+
+	#[test]
+	fn iter_separated() {
+		let base = "a, bb, ccc";
+		let token =
+			(alphabetic, optional(", ")).map_out(|(word, _)| word);
+		let spans: Vec<_> = iter_spanned(base, token, base)
+			.map(|(word, span)| (word, span.start(), span.end()))
+			.collect();
+
+		assert_eq!(
+			vec![("a", 0, 3), ("bb", 3, 7), ("ccc", 7, 10)],
+			spans
+		);
+	}
+}
@@ -2,12 +2,28 @@
 //!
 //! All of the parsers return [`Error`] for easier compositon.
 
+#[cfg(feature = "alloc")]
+use alloc::{
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
 use core::num::{ParseFloatError, ParseIntError};
 
-use crate::{combinator::choice, PResult, Parser};
+#[cfg(feature = "alloc")]
+use crate::combinator::{many0, separated_list0, separated_list1, verify};
+use crate::{
+	combinator::{
+		self, choice, delimited, optional, preceded, recognize,
+		terminated, TakeUnits,
+	},
+	span::Span,
+	PResult, ParseError, Parser, Sliceable,
+};
 
 /// TODO: docs
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error<'a> {
 	/// The parser unexpectedly reached the end of the input.
 	End {
@@ -22,7 +38,11 @@ pub enum Error<'a> {
 		span: &'a str,
 	},
 	/// Returned by [`eof`] when the input isn't empty.
-	NotEnd,
+	NotEnd {
+		/// The first character of the remaining input, which marks
+		/// where the trailing input begins.
+		span: &'a str,
+	},
 	/// Failed to parse an integer.
 	ParseInt {
 		/// The error returned by the integer `from_str` and
@@ -38,6 +58,57 @@ pub enum Error<'a> {
 		/// The input substring which was parsed.
 		span: &'a str,
 	},
+	/// A failure described by a message, for example when a combinator
+	/// rejects the output of a parser.
+	Message {
+		/// The description of the failure.
+		message: &'static str,
+		/// The part of the input where the failure occurred.  It is
+		/// zero-width if the failure has no natural extent.
+		span: &'a str,
+	},
+	/// An unknown word was found where a keyword was expected, see
+	/// [`keyword_suggest`].
+	Suggestion {
+		/// The unknown word.
+		span: &'a str,
+		/// The closest keyword.
+		suggestion: &'static str,
+	},
+	/// None of the alternatives of [`choice`] matched.
+	#[cfg(feature = "alloc")]
+	Expected {
+		/// The literals which the alternatives expected, without
+		/// duplicates.
+		expected: Vec<String>,
+		/// A zero-width slice which points to where the alternatives were
+		/// tried.
+		span: &'a str,
+	},
+	/// The value of a [`field`] failed to parse.
+	#[cfg(feature = "alloc")]
+	Field {
+		/// The name of the field.
+		name: &'static str,
+		/// The error returned by the value parser.
+		error: Box<Error<'a>>,
+	},
+	/// A frame added by [`stacked`][crate::combinator::stacked] or
+	/// [`Error::with_context`] around an error returned by a nested parser.
+	#[cfg(feature = "alloc")]
+	Context {
+		/// The name of the parser which failed.
+		label: &'static str,
+		/// The error returned by the parser.
+		error: Box<Error<'a>>,
+	},
+	/// An error marked by [`cut`][crate::combinator::cut], which stops
+	/// alternatives from being tried.
+	#[cfg(feature = "alloc")]
+	Cut {
+		/// The marked error.
+		error: Box<Error<'a>>,
+	},
 }
 
 use core::fmt;
@@ -53,7 +124,11 @@ impl fmt::Display for Error<'_> {
 					"Parser failed to match '{literal}'"
 				))?;
 			}
-			Error::NotEnd => f.write_str("Input not empty")?,
+			Error::NotEnd { span } => {
+				f.write_fmt(format_args!(
+					"Expected end of input, found '{span}'"
+				))?;
+			}
 			Error::ParseInt { error, span } => {
 				f.write_fmt(format_args!(
 					"Failed to parse integer '{span}': "
@@ -66,42 +141,290 @@ impl fmt::Display for Error<'_> {
 				))?;
 				error.fmt(f)?;
 			}
+			Error::Message { message, .. } => {
+				f.write_str(message)?
+			}
+			Error::Suggestion { span, suggestion } => {
+				f.write_fmt(format_args!(
+					"Unknown keyword '{span}', did you mean \
+					 '{suggestion}'?"
+				))?;
+			}
+			#[cfg(feature = "alloc")]
+			Error::Expected { expected, .. } => {
+				f.write_str("Expected one of: ")?;
+				for (i, literal) in expected.iter().enumerate()
+				{
+					if i > 0 {
+						f.write_str(", ")?;
+					}
+					f.write_fmt(format_args!(
+						"'{literal}'"
+					))?;
+				}
+			}
+			#[cfg(feature = "alloc")]
+			Error::Field { name, error } => {
+				f.write_fmt(format_args!(
+					"While parsing field '{name}': {error}"
+				))?;
+			}
+			#[cfg(feature = "alloc")]
+			Error::Context { label, error } => {
+				f.write_fmt(format_args!("{label} → {error}"))?;
+			}
+			#[cfg(feature = "alloc")]
+			Error::Cut { error } => error.fmt(f)?,
 		}
 
 		Ok(())
 	}
 }
 
-impl core::error::Error for Error<'_> {}
+impl core::error::Error for Error<'_> {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		match self {
+			Error::ParseInt { error, .. } => Some(error),
+			Error::ParseFloat { error, .. } => Some(error),
+			_ => None,
+		}
+	}
+}
 
-impl Error<'_> {
+impl<'a> Error<'a> {
 	/// Creates a new `End` error which points to the end of `input`.
-	fn end(input: &str) -> Error {
+	fn end(input: &str) -> Error<'_> {
 		let ptr = &input[input.len()..input.len()];
 		Error::End { span: ptr }
 	}
 
 	/// Creates a new `Unmatched` error with a given span.
-	pub fn unmatched(span: &str) -> Error {
+	pub fn unmatched(span: &str) -> Error<'_> {
 		Error::Unmatched { span }
 	}
+
+	/// Returns the part of the input where the error occurred.
+	///
+	/// For the errors which wrap another one, this is the span of the
+	/// innermost error.
+	pub fn span(&self) -> &'a str {
+		match self {
+			Error::End { span }
+			| Error::Unmatched { span }
+			| Error::NotEnd { span }
+			| Error::ParseInt { span, .. }
+			| Error::ParseFloat { span, .. }
+			| Error::Message { span, .. }
+			| Error::Suggestion { span, .. } => span,
+			#[cfg(feature = "alloc")]
+			Error::Expected { span, .. } => span,
+			#[cfg(feature = "alloc")]
+			Error::Field { error, .. }
+			| Error::Context { error, .. }
+			| Error::Cut { error } => error.span(),
+		}
+	}
+
+	/// Wraps the error in a [`Context`][Error::Context] frame named
+	/// `label`.
+	#[cfg(feature = "alloc")]
+	pub fn with_context(self, label: &'static str) -> Self {
+		Error::Context {
+			label,
+			error: Box::new(self),
+		}
+	}
+
+	/// Returns the labels of the [`Context`][Error::Context] frames, from
+	/// the outermost to the innermost one.
+	#[cfg(feature = "alloc")]
+	pub fn context_stack(&self) -> Vec<&'static str> {
+		let mut labels = Vec::new();
+		let mut error = self;
+
+		loop {
+			match error {
+				Error::Context {
+					label,
+					error: inner,
+				} => {
+					labels.push(*label);
+					error = inner;
+				}
+				Error::Field { error: inner, .. }
+				| Error::Cut { error: inner } => error = inner,
+				_ => return labels,
+			}
+		}
+	}
+
+	/// Returns the location of the [`span`][Self::span] in `source`, or
+	/// `None` if the error was produced from a different string.
+	///
+	/// ```rust
+	/// use komb::{Parser, span::Span, string::u32};
+	///
+	/// let source = "1, 2; 3";
+	/// let error = (u32, ", ", u32, ", ", u32).parse(source).unwrap_err();
+	///
+	/// assert_eq!(Some(Span::new(4, 6)), error.span_in(source));
+	/// assert_eq!(None, error.span_in("other"));
+	/// ```
+	pub fn span_in(&self, source: &str) -> Option<Span> {
+		Span::of(source, self.span())
+	}
+
+	/// Renders the error for the user: the line and column, the message, the
+	/// line of `source` containing the error, and the span underlined.
+	///
+	/// A span which continues on the following lines is only underlined up
+	/// to the end of its first line.  If the error wasn't produced from
+	/// `source`, only the message is returned.
+	///
+	/// ```rust
+	/// use komb::{Parser, string::u32};
+	///
+	/// let source = "x = 1\ny = 2; 3";
+	/// let p = (u32, ", ", u32);
+	/// let error = p.parse(&source[10..]).unwrap_err();
+	///
+	/// assert_eq!(
+	///     "2:6: Parser failed to match '; '\ny = 2; 3\n     ^~",
+	///     error.render(source),
+	/// );
+	/// assert_eq!(error.to_string(), error.render("other"));
+	/// ```
+	#[cfg(feature = "alloc")]
+	pub fn render(&self, source: &str) -> String {
+		let Some(span) = self.span_in(source) else {
+			return self.to_string();
+		};
+		let (line, column) = span.line_col(source);
+
+		let start = span.start();
+		let line_start =
+			source[..start].rfind('\n').map_or(0, |i| i + 1);
+		let line_end = source[start..]
+			.find('\n')
+			.map_or(source.len(), |i| start + i);
+		let end = span.end().min(line_end);
+
+		let mut output = format!("{line}:{column}: {self}\n");
+		output.push_str(
+			source[line_start..line_end].trim_end_matches('\r'),
+		);
+		output.push('\n');
+		// tabs are kept, so that the caret lines up with the text
+		for ch in source[line_start..start].chars() {
+			output.push(if ch == '\t' { '\t' } else { ' ' });
+		}
+		output.push('^');
+		for _ in source[start..end].chars().skip(1) {
+			output.push('~');
+		}
+
+		output
+	}
+}
+
+impl<'a> ParseError<&'a str> for Error<'a> {
+	fn message(input: &'a str, message: &'static str) -> Self {
+		Error::Message {
+			message,
+			span: &input[..0],
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	fn cut(self) -> Self {
+		if self.is_cut() {
+			return self;
+		}
+
+		Error::Cut {
+			error: Box::new(self),
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	fn is_cut(&self) -> bool {
+		match self {
+			Error::Cut { .. } => true,
+			Error::Field { error, .. }
+			| Error::Context { error, .. } => error.is_cut(),
+			_ => false,
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	fn context(self, label: &'static str) -> Self {
+		self.with_context(label)
+	}
+
+	#[cfg(feature = "alloc")]
+	fn one_of(
+		input: &'a str,
+		expected: &mut dyn Iterator<Item = Option<&str>>,
+		last: Self,
+	) -> Self {
+		let mut literals: Vec<String> = Vec::new();
+		for literal in expected {
+			// an alternative which isn't a literal can't be listed
+			let Some(literal) = literal else {
+				return last;
+			};
+			if !literals.iter().any(|known| known == literal) {
+				literals.push(literal.to_string());
+			}
+		}
+
+		Error::Expected {
+			expected: literals,
+			span: &input[..0],
+		}
+	}
 }
 
 /// Returns the prefix which the inner parser consumed as output.
+///
+/// This is [`recognize`] for `str` input.
 pub fn consume<'a, O, E>(
 	parser: impl Parser<'a, &'a str, O, E>,
 ) -> impl Parser<'a, &'a str, &'a str, E> {
-	move |input: &'a str| {
-		let (_, rest) = parser.parse(input)?;
-
-		let start = input.as_ptr() as usize;
-		let end = rest.as_ptr() as usize;
+	recognize(parser)
+}
 
-		assert!(start < end);
-		let length = end - start;
-		assert!(length <= input.len());
+/// Returns both the output of the inner parser and the prefix it consumed.
+///
+/// This is [`with_recognized`][crate::combinator::with_recognized] for
+/// `str` input.
+pub fn with_recognized<'a, O, E>(
+	parser: impl Parser<'a, &'a str, O, E>,
+) -> impl Parser<'a, &'a str, (O, &'a str), E> {
+	combinator::with_recognized(parser)
+}
 
-		Ok((&input[..length], &input[length..]))
+/// Returns the output of the inner parser and the number of characters it
+/// consumed.
+///
+/// Unlike [`Span`], which is measured in bytes, the count is in Unicode
+/// scalar values, which is what a column position is usually made of.
+///
+/// ```rust
+/// use komb::{Parser, string::{alphabetic, with_char_len}};
+///
+/// let p = with_char_len(alphabetic);
+///
+/// assert_eq!(Ok((("Grüße", 5), "!")), p.parse("Grüße!"));
+/// assert_eq!(7, "Grüße".len());
+/// ```
+pub fn with_char_len<'a, O, E>(
+	parser: impl Parser<'a, &'a str, O, E>,
+) -> impl Parser<'a, &'a str, (O, usize), E> {
+	move |input: &'a str| {
+		let (out, rest) = parser.parse(input)?;
+		let length = input.consumed(rest).chars().count();
+		Ok(((out, length), rest))
 	}
 }
 
@@ -130,6 +453,10 @@ impl<'a> Parser<'a, &'a str, &'a str, Error<'a>> for &str {
 			Err(Error::unmatched(&input[..self.len()]))
 		}
 	}
+
+	fn expected(&self) -> Option<&str> {
+		Some(self)
+	}
 }
 
 impl<'a> Parser<'a, &'a str, &'a str, Error<'a>> for char {
@@ -177,9 +504,7 @@ pub fn anycase<'a>(
 				return Err(Error::end(input));
 			};
 
-			if lit_ch.to_ascii_lowercase()
-				!= input_ch.to_ascii_lowercase()
-			{
+			if !lit_ch.eq_ignore_ascii_case(&input_ch) {
 				return Err(Error::unmatched(
 					&input[..i + input_ch.len_utf8()],
 				));
@@ -188,359 +513,2275 @@ pub fn anycase<'a>(
 	}
 }
 
-/// Matches either a `\n` or `\r\n` line ending, returns it as an `&str`
-/// reference.
+/// Matches whichever of the two literals is longer if both of them match.
+///
+/// This is useful when one literal is a prefix of another, like `<` and `<=`,
+/// since [`choice`] would always pick the first one which succeeds.  If
+/// neither literal matches, the error from `b` is returned.
 ///
 /// ```rust
-/// use komb::{Parser, string::{line_end, alphanumeric}};
+/// use komb::{Parser, string::longest_tag};
 ///
-/// let p = alphanumeric.before(line_end);
+/// let p = longest_tag("<", "<=");
 ///
-/// assert_eq!(Ok(("Hello", "world")), p.parse("Hello\nworld"));
+/// assert_eq!(Ok(("<=", " 1")), p.parse("<= 1"));
+/// assert_eq!(Ok(("<", " 1")), p.parse("< 1"));
+/// assert!(p.parse("> 1").is_err());
 /// ```
-pub fn line_end(input: &str) -> PResult<&str, &str, Error> {
-	choice(("\n", "\r\n")).parse(input)
+pub fn longest_tag<'a>(
+	a: &'static str,
+	b: &'static str,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	move |input: &'a str| match (
+		Parser::parse(&a, input),
+		Parser::parse(&b, input),
+	) {
+		(Ok((a_out, a_rest)), Ok((b_out, b_rest))) => {
+			if a_out.len() >= b_out.len() {
+				Ok((a_out, a_rest))
+			} else {
+				Ok((b_out, b_rest))
+			}
+		}
+		(Ok(a), Err(_)) => Ok(a),
+		(Err(_), b) => b,
+	}
 }
 
-/// Matches a single `\n`-terminated line.
+/// Matches text which is byte-identical to `expected`, a span parsed earlier.
 ///
-/// Returns the whole line excluding the terminating newline character.  If the
-/// line ended in `\r\n`, carriage return will be part of the output.
+/// Unlike literals, `expected` is usually not known until parsing, like the
+/// name of an XML tag which has to be repeated in the closing tag.
 ///
 /// ```rust
-/// use komb::{Parser, string::line};
+/// use komb::{Parser, combinator::delimited};
+/// use komb::string::{alphabetic, match_same};
 ///
-/// assert_eq!(Ok(("Hello", "world")), line.parse("Hello\nworld"));
-/// assert_eq!(Ok(("Hello\r", "world")), line.parse("Hello\r\nworld"));
-/// assert_eq!(Ok(("", "next line")), line.parse("\nnext line"));
-/// assert!(line.parse("").is_err());
-/// // No newline at the end
-/// assert!(line.parse("Hello there").is_err());
+/// let input = "<b>bold</b> rest";
+/// let (name, rest) = delimited("<", alphabetic, ">").parse(input).unwrap();
+/// let (_, rest) = alphabetic.parse(rest).unwrap();
+///
+/// let close = delimited("</", match_same(name), ">");
+/// assert_eq!(Ok(("b", " rest")), close.parse(rest));
+/// assert!(close.parse("</i>").is_err());
 /// ```
-pub fn line(input: &str) -> PResult<&str, &str, Error> {
-	or0(none_of(&['\n'])).before(line_end).parse(input)
+pub fn match_same<'a>(
+	expected: &'a str,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	move |input: &'a str| Parser::parse(&expected, input)
 }
 
-/// Succeeds if the input is empty.
+/// Returns the number of single-character insertions, deletions and
+/// substitutions needed to turn `a` into `b`.
+#[cfg(feature = "alloc")]
+fn edit_distance(a: &str, b: &str) -> usize {
+	let distances = prefix_distances(a, b);
+	distances[distances.len() - 1]
+}
+
+/// Returns the edit distances between `a` and every prefix of `b`, indexed by
+/// the prefix length in characters.
+#[cfg(feature = "alloc")]
+fn prefix_distances(a: &str, b: &str) -> Vec<usize> {
+	let mut previous: Vec<usize> = (0..=b.chars().count()).collect();
+	let mut current = Vec::with_capacity(previous.len());
+
+	for (i, a_ch) in a.chars().enumerate() {
+		current.clear();
+		current.push(i + 1);
+		for (j, b_ch) in b.chars().enumerate() {
+			let substitution =
+				previous[j] + usize::from(a_ch != b_ch);
+			let deletion = previous[j + 1] + 1;
+			let insertion = current[j] + 1;
+			current.push(substitution.min(deletion).min(insertion));
+		}
+		core::mem::swap(&mut previous, &mut current);
+	}
+
+	previous
+}
+
+/// Matches the longest of the `keywords`, suggesting the closest one on
+/// failure.
+///
+/// If none of the keywords match, the word at the start of the input (a run
+/// of alphanumeric characters and underscores) is compared to the keywords.
+/// If the closest one by edit distance differs in at most half of its
+/// characters, [`Error::Suggestion`] is returned.  Otherwise the error is
+/// [`Error::Unmatched`].
 ///
 /// ```rust
-/// use komb::{Parser, string::eof};
+/// use komb::{Parser, string::{keyword_suggest, Error}};
 ///
-/// let p = "Hello world".before(eof);
+/// let p = keyword_suggest(&["select", "insert", "delete"]);
 ///
-/// assert_eq!(Ok(("Hello world", "")), p.parse("Hello world"));
-/// assert!(p.parse("Hello world and then some").is_err());
+/// assert_eq!(Ok(("select", " *")), p.parse("select *"));
+/// assert_eq!(
+///     Err(Error::Suggestion { span: "selct", suggestion: "select" }),
+///     p.parse("selct *"),
+/// );
+/// assert_eq!(
+///     "Unknown keyword 'inserrt', did you mean 'insert'?",
+///     p.parse("inserrt").unwrap_err().to_string(),
+/// );
+/// assert_eq!(Err(Error::Unmatched { span: "drop" }), p.parse("drop"));
 /// ```
-pub fn eof(input: &str) -> PResult<&str, (), Error> {
-	if input.is_empty() {
-		Ok(((), input))
-	} else {
-		Err(Error::NotEnd)
+#[cfg(feature = "alloc")]
+pub fn keyword_suggest<'a>(
+	keywords: &'a [&'static str],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	move |input: &'a str| {
+		let longest = keywords
+			.iter()
+			.filter(|keyword| input.starts_with(**keyword))
+			.max_by_key(|keyword| keyword.len());
+		if let Some(keyword) = longest {
+			return Ok((
+				&input[..keyword.len()],
+				&input[keyword.len()..],
+			));
+		}
+
+		let (word, _) = take_while(|c| c.is_alphanumeric() || c == '_')
+			.parse(input)?;
+		let closest = keywords
+			.iter()
+			.map(|keyword| (keyword, edit_distance(word, keyword)))
+			.min_by_key(|(_, distance)| *distance);
+
+		match closest {
+			Some((keyword, distance))
+				if distance * 2 <= keyword.chars().count() =>
+			{
+				Err(Error::Suggestion {
+					span: word,
+					suggestion: keyword,
+				})
+			}
+			_ => Err(Error::unmatched(word)),
+		}
 	}
 }
 
-/// Takes exactly `length` characters (not bytes) from the input.  Returns
-/// [`Error::End`] if the string isn't long enough.
-pub fn take<'a>(length: usize) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+/// Matches a prefix of the input which is within `max_edits` edit distance
+/// (Levenshtein) of the `literal` and returns it.
+///
+/// The matching is greedy over a bounded window: only prefixes of at most
+/// `max_edits` characters more than the literal are considered, and of those
+/// the closest one is picked, preferring the longest one on ties.  This is
+/// useful for forgiving command parsers which tolerate typos.
+///
+/// If no prefix is close enough, [`Error::Unmatched`] with the window is
+/// returned, or [`Error::End`] if the input is too short.
+///
+/// ```rust
+/// use komb::{Parser, string::fuzzy_tag};
+///
+/// let p = fuzzy_tag("select", 1);
+///
+/// assert_eq!(Ok(("select", " *")), p.parse("select *"));
+/// assert_eq!(Ok(("selct", " *")), p.parse("selct *"));
+/// assert_eq!(Ok(("seleect", " *")), p.parse("seleect *"));
+/// assert!(p.parse("slct *").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fuzzy_tag<'a>(
+	literal: &'static str,
+	max_edits: usize,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
 	move |input: &'a str| {
-		let mut current_length = 0;
-		for (i, ch) in input.char_indices() {
-			current_length += 1;
-			if current_length == length {
-				let split = i + ch.len_utf8();
-				return Ok((&input[..split], &input[split..]));
+		let literal_len = literal.chars().count();
+		let window =
+			match input.char_indices().nth(literal_len + max_edits)
+			{
+				Some((i, _)) => &input[..i],
+				None => input,
+			};
+
+		let ends = window
+			.char_indices()
+			.map(|(i, _)| i)
+			.chain([window.len()]);
+		let closest = prefix_distances(literal, window)
+			.into_iter()
+			.zip(ends)
+			.filter(|(distance, _)| *distance <= max_edits)
+			.min_by_key(|(distance, end)| {
+				(*distance, core::cmp::Reverse(*end))
+			});
+
+		match closest {
+			Some((_, end)) => Ok(input.split_at(end)),
+			None if window.chars().count() + max_edits
+				< literal_len =>
+			{
+				Err(Error::end(input))
 			}
+			None => Err(Error::unmatched(window)),
 		}
-
-		Err(Error::end(input))
 	}
 }
 
-/// Cuts off a prefix of a string for whose characters the predicate `f` returns
-/// `true`.
+/// Matches `parser` and fails with [`Error::Unmatched`] if its output is one
+/// of the `reserved` words.
+///
+/// This is useful for identifiers, which mustn't be keywords.
 ///
 /// ```rust
-/// use komb::{Parser, string::take_while};
+/// use komb::{Parser, string::{alphabetic, not_reserved}};
 ///
-/// let p = take_while(|ch| ch == '0' || ch == '1');
+/// let ident = not_reserved(alphabetic, &["if", "while"]);
 ///
-/// assert_eq!(Ok(("01010", "rest")), p.parse("01010rest"));
-/// assert!(p.parse("other").is_err());
+/// assert_eq!(Ok(("foo", " bar")), alphabetic.parse("foo bar"));
+/// assert_eq!(Ok(("foo", " bar")), ident.parse("foo bar"));
+/// assert_eq!(Ok(("while", " x")), alphabetic.parse("while x"));
+/// assert!(ident.parse("while x").is_err());
+/// // only whole words are reserved
+/// assert_eq!(Ok(("iffy", "")), ident.parse("iffy"));
 /// ```
-pub fn take_while<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
-where
-	F: Fn(char) -> bool + 'a,
-{
+pub fn not_reserved<'a>(
+	parser: impl Parser<'a, &'a str, &'a str, Error<'a>> + 'a,
+	reserved: &'a [&'static str],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
 	move |input: &'a str| {
-		let mut index = 0;
-		let mut at_least_one = false;
+		let (word, rest) = parser.parse(input)?;
 
-		for (i, char) in input.char_indices() {
-			if !f(char) {
-				return if at_least_one {
-					Ok((&input[..i], &input[i..]))
-				} else {
-					Err(Error::end(input))
-				};
-			}
-			index = i + char.len_utf8();
-			at_least_one = true;
+		if reserved.contains(&word) {
+			return Err(Error::Unmatched { span: word });
 		}
 
-		if at_least_one {
-			Ok((&input[..index], &input[index..]))
-		} else {
-			Err(Error::end(input))
+		Ok((word, rest))
+	}
+}
+
+/// The kind of brackets matched by [`any_bracketed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BracketKind {
+	/// `(` and `)`.
+	Round,
+	/// `[` and `]`.
+	Square,
+	/// `{` and `}`.
+	Curly,
+}
+
+impl BracketKind {
+	/// Returns the opening and the closing bracket.
+	pub fn chars(self) -> (char, char) {
+		match self {
+			BracketKind::Round => ('(', ')'),
+			BracketKind::Square => ('[', ']'),
+			BracketKind::Curly => ('{', '}'),
 		}
 	}
 }
 
-/// Matches a prefix until the first character which satisfies the predicate.
-pub fn take_until<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+/// Matches `content` wrapped in round, square or curly brackets and returns
+/// the kind of the brackets with the output of `content`.
+///
+/// The closing bracket has to be of the same kind as the opening one.
+///
+/// ```rust
+/// use komb::{Parser, string::{any_bracketed, u32, BracketKind}};
+///
+/// let p = any_bracketed(u32);
+///
+/// assert_eq!(Ok(((BracketKind::Round, 1), "")), p.parse("(1)"));
+/// assert_eq!(Ok(((BracketKind::Square, 2), "")), p.parse("[2]"));
+/// assert_eq!(Ok(((BracketKind::Curly, 3), "")), p.parse("{3}"));
+/// assert!(p.parse("(4]").is_err());
+/// assert!(p.parse("<5>").is_err());
+/// ```
+pub fn any_bracketed<'a, O>(
+	content: impl Parser<'a, &'a str, O, Error<'a>> + 'a,
+) -> impl Parser<'a, &'a str, (BracketKind, O), Error<'a>>
 where
-	F: Fn(char) -> bool + 'a,
+	O: 'a,
 {
-	take_while(move |c| !f(c))
-}
+	move |input: &'a str| {
+		let kind = choice((
+			'('.value(BracketKind::Round),
+			'['.value(BracketKind::Square),
+			'{'.value(BracketKind::Curly),
+		));
+		let (kind, rest) = kind.parse(input)?;
+		let (_, close) = kind.chars();
 
-/// Matches the characters in `chars`.
-pub fn one_of<'a, 'c: 'a>(
-	chars: &'c [char],
-) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
-	take_while(move |c| chars.contains(&c))
-}
+		let (out, rest) = content.parse(rest)?;
+		let (_, rest) = close.parse(rest)?;
 
-/// Matches the characters not in `chars`.
-pub fn none_of<'a, 'c: 'a>(
-	chars: &'c [char],
-) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
-	take_until(move |c| chars.contains(&c))
+		Ok(((kind, out), rest))
+	}
 }
 
-/// Matches Unicode whitespace.
+/// Parses a boolean written as `true` or `false`.
 ///
-/// Uses [`char::is_whitespace`].
-pub fn whitespace(input: &str) -> PResult<&str, &str, Error> {
-	take_while(|c| c.is_whitespace()).parse(input)
-}
-
-/// Matches alphabetic characters.
+/// The case matters.  See [`boolean_lenient`] for a parser which accepts more
+/// spellings and [`boolean_with`] for other sets of words.
 ///
-/// Uses [`char::is_alphabetic`].
-pub fn alphanumeric(input: &str) -> PResult<&str, &str, Error> {
-	take_while(|c| c.is_alphanumeric()).parse(input)
+/// ```rust
+/// use komb::{Parser, string::boolean};
+///
+/// assert_eq!(Ok((true, ",")), boolean.parse("true,"));
+/// assert_eq!(Ok((false, "")), boolean.parse("false"));
+/// assert!(boolean.parse("True").is_err());
+/// assert!(boolean.parse("yes").is_err());
+/// ```
+pub fn boolean(input: &str) -> PResult<&str, bool, Error<'_>> {
+	boolean_with(&["true"], &["false"], false).parse(input)
 }
 
-/// Matches alphanumeric characters.
+/// Parses a boolean written as `true`/`false`, `yes`/`no`, `on`/`off` or
+/// `1`/`0`, ignoring the ASCII case.
 ///
-/// Uses [`char::is_alphanumeric`].
+/// See [`boolean_with`] for the details and for other sets of words.
 ///
 /// ```rust
-/// use komb::{Parser, string::alphabetic};
-///
-/// let p = alphabetic;
+/// use komb::{Parser, string::boolean_lenient};
 ///
-/// assert_eq!(Ok(("abcXYZ", " rest")), p.parse("abcXYZ rest"));
-/// assert!(p.parse("_ident").is_err());
+/// assert_eq!(Ok((true, "")), boolean_lenient.parse("YES"));
+/// assert_eq!(Ok((false, "")), boolean_lenient.parse("off"));
+/// assert_eq!(Ok((true, ";")), boolean_lenient.parse("1;"));
+/// assert!(boolean_lenient.parse("maybe").is_err());
+/// assert!(boolean_lenient.parse("10").is_err());
 /// ```
-pub fn alphabetic(input: &str) -> PResult<&str, &str, Error> {
-	take_while(|c| c.is_alphabetic()).parse(input)
+pub fn boolean_lenient(input: &str) -> PResult<&str, bool, Error<'_>> {
+	boolean_with(
+		&["true", "yes", "on", "1"],
+		&["false", "no", "off", "0"],
+		true,
+	)
+	.parse(input)
 }
 
-// Character combinators
-
-/// Returns the first character in input if it satisfies the predicate.
-///
-/// If the predicate fails, [`Error::Unmatched`] is returned.  If the string is
-/// empty, [`Error::End`] is returned.
+/// Parses a boolean written as one of the `truthy` or `falsy` words.  If
+/// `ignore_case` is set, the ASCII case of the words doesn't matter.
 ///
-/// This function returns a borrowed string slice `&str` to preserve the
-/// location of the character.
+/// The longest matching word is picked.  A word followed by an alphanumeric
+/// character doesn't match, so `none` isn't read as `no` and `10` isn't read
+/// as `1`.  If no word matches, returns [`Error::Unmatched`] pointing to the
+/// first character.
 ///
 /// ```rust
-/// use komb::{Parser, string::char};
+/// use komb::{Parser, string::boolean_with};
 ///
-/// let p = char(|ch| ch == '1' || ch == 'a');
+/// let p = boolean_with(&["enabled"], &["disabled", "dis"], true);
 ///
-/// assert_eq!(Ok(("1", "rest")), p.parse("1rest"));
-/// assert_eq!(Ok(("a", "1")), p.parse("a1"));
-/// assert!(p.parse("x").is_err());
-/// ```
-pub fn char<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+/// assert_eq!(Ok((true, "")), p.parse("Enabled"));
+/// assert_eq!(Ok((false, " now")), p.parse("disabled now"));
+/// assert_eq!(Ok((false, "")), p.parse("dis"));
+/// assert!(p.parse("yes").is_err());
+///
+/// let p = boolean_with(&["on"], &["off"], false);
+///
+/// assert_eq!(Ok((false, " now")), p.parse("off now"));
+/// assert!(p.parse("On").is_err());
+/// assert!(p.parse("onward").is_err());
+/// ```
+pub fn boolean_with<'a>(
+	truthy: &'a [&'static str],
+	falsy: &'a [&'static str],
+	ignore_case: bool,
+) -> impl Parser<'a, &'a str, bool, Error<'a>> {
+	move |input: &'a str| {
+		let words = truthy
+			.iter()
+			.map(|word| (*word, true))
+			.chain(falsy.iter().map(|word| (*word, false)));
+
+		longest_boolean(input, words, ignore_case)
+	}
+}
+
+/// Matches the longest of the boolean `words` which isn't followed by an
+/// alphanumeric character.
+fn longest_boolean<'a>(
+	input: &'a str,
+	words: impl Iterator<Item = (&'static str, bool)>,
+	ignore_case: bool,
+) -> PResult<&'a str, bool, Error<'a>> {
+	let longest = words
+		.filter_map(|(word, value)| {
+			let (_, rest) = if ignore_case {
+				anycase(word).parse(input).ok()?
+			} else {
+				Parser::parse(&word, input).ok()?
+			};
+			let boundary = !rest.starts_with(char::is_alphanumeric);
+			boundary.then_some((value, rest))
+		})
+		.min_by_key(|(_, rest)| rest.len());
+
+	match (longest, input.chars().next()) {
+		(Some((value, rest)), _) => Ok((value, rest)),
+		(None, Some(ch)) => {
+			Err(Error::unmatched(&input[..ch.len_utf8()]))
+		}
+		(None, None) => Err(Error::end(input)),
+	}
+}
+
+/// Matches either a `\n` or `\r\n` line ending, returns it as an `&str`
+/// reference.
+///
+/// ```rust
+/// use komb::{Parser, string::{line_end, alphanumeric}};
+///
+/// let p = alphanumeric.before(line_end);
+///
+/// assert_eq!(Ok(("Hello", "world")), p.parse("Hello\nworld"));
+/// ```
+pub fn line_end(input: &str) -> PResult<&str, &str, Error<'_>> {
+	choice(("\n", "\r\n")).parse(input)
+}
+
+/// Matches a single `\n`-terminated line.
+///
+/// Returns the whole line excluding the terminating newline character.  If the
+/// line ended in `\r\n`, carriage return will be part of the output.
+///
+/// ```rust
+/// use komb::{Parser, string::line};
+///
+/// assert_eq!(Ok(("Hello", "world")), line.parse("Hello\nworld"));
+/// assert_eq!(Ok(("Hello\r", "world")), line.parse("Hello\r\nworld"));
+/// assert_eq!(Ok(("", "next line")), line.parse("\nnext line"));
+/// assert!(line.parse("").is_err());
+/// // No newline at the end
+/// assert!(line.parse("Hello there").is_err());
+/// ```
+pub fn line(input: &str) -> PResult<&str, &str, Error<'_>> {
+	or0(none_of(&['\n'])).before(line_end).parse(input)
+}
+
+/// Skips a leading `#!` interpreter line, including its line ending.
+///
+/// If the input doesn't start with `#!`, nothing is consumed.  A shebang
+/// without a newline takes up the whole input.  This never fails.
+///
+/// ```rust
+/// use komb::{Parser, string::skip_shebang};
+///
+/// let script = "#!/usr/bin/env komb\nprint 1\n";
+/// assert_eq!(Ok(((), "print 1\n")), skip_shebang.parse(script));
+/// assert_eq!(Ok(((), "print 1\n")), skip_shebang.parse("print 1\n"));
+/// ```
+pub fn skip_shebang(input: &str) -> PResult<&str, (), Error<'_>> {
+	if !input.starts_with("#!") {
+		return Ok(((), input));
+	}
+
+	let rest = match input.find('\n') {
+		Some(i) => &input[i + 1..],
+		None => &input[input.len()..],
+	};
+	Ok(((), rest))
+}
+
+/// Parses a line and returns the width of its indentation together with the
+/// rest of the line.
+///
+/// The width is counted in columns: a space is one column, and a tab moves to
+/// the next multiple of `tab_width`.  The line ending isn't part of the
+/// content.  The last line doesn't need to end with a newline.
+///
+/// # Panics
+///
+/// Panics if `tab_width` is zero.
+///
+/// ```rust
+/// use komb::{Parser, string::indented_line};
+///
+/// let p = indented_line(4);
+///
+/// assert_eq!(Ok(((2, "item"), "next")), p.parse("  item\r\nnext"));
+/// assert_eq!(Ok(((8, "item"), "")), p.parse("  \t\titem"));
+/// ```
+pub fn indented_line<'a>(
+	tab_width: usize,
+) -> impl Parser<'a, &'a str, (usize, &'a str), Error<'a>> {
+	assert!(tab_width > 0);
+
+	move |input: &'a str| {
+		let (indent, rest) = or0(one_of(&[' ', '\t'])).parse(input)?;
+		let (content, rest) =
+			or0(take_until(|ch| ch == '\r' || ch == '\n'))
+				.parse(rest)?;
+		let (_, rest) =
+			choice((line_end.value(()), eof)).parse(rest)?;
+
+		let depth = indent.chars().fold(0, |column, ch| match ch {
+			'\t' => (column / tab_width + 1) * tab_width,
+			_ => column + 1,
+		});
+
+		Ok(((depth, content), rest))
+	}
+}
+
+/// Parses indented lines until the end of the input, see [`indented_line`].
+/// Blank lines are skipped.
+///
+/// This is a building block for outline formats, where the nesting is given
+/// by the indentation.
+///
+/// # Panics
+///
+/// Panics if `tab_width` is zero.
+///
+/// ```rust
+/// use komb::{Parser, string::indented_lines};
+///
+/// let input = "fruits\n\tapple\n    pear\n\n  \t  red\nvegetables\n";
+/// let (lines, rest) = indented_lines(4).parse(input).unwrap();
+///
+/// assert_eq!("", rest);
+/// assert_eq!(
+///     vec![(0, "fruits"), (4, "apple"), (4, "pear"), (6, "red"), (0, "vegetables")],
+///     lines,
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn indented_lines<'a>(
+	tab_width: usize,
+) -> impl Parser<'a, &'a str, Vec<(usize, &'a str)>, Error<'a>> {
+	many0(indented_line(tab_width)).map_out(|mut lines| {
+		lines.retain(|(_, content)| !content.is_empty());
+		lines
+	})
+}
+
+/// Succeeds if the input is empty.
+///
+/// Otherwise returns [`Error::NotEnd`], which points to the first remaining
+/// character.
+///
+/// ```rust
+/// use komb::{Parser, string::eof};
+///
+/// let p = "Hello world".before(eof);
+///
+/// assert_eq!(Ok(("Hello world", "")), p.parse("Hello world"));
+/// assert!(p.parse("Hello world and then some").is_err());
+/// ```
+pub fn eof(input: &str) -> PResult<&str, (), Error<'_>> {
+	match input.chars().next() {
+		None => Ok(((), input)),
+		Some(ch) => Err(Error::NotEnd {
+			span: &input[..ch.len_utf8()],
+		}),
+	}
+}
+
+/// Returns the whole remaining input.  Never fails.
+///
+/// ```rust
+/// use komb::{Parser, string::rest};
+///
+/// let p = ("Subject: ", rest);
+///
+/// assert_eq!(Ok((("Subject: ", "hello"), "")), p.parse("Subject: hello"));
+/// assert_eq!(Ok((("Subject: ", ""), "")), p.parse("Subject: "));
+/// ```
+pub fn rest(input: &str) -> PResult<&str, &str, Error<'_>> {
+	Ok((input, &input[input.len()..]))
+}
+
+/// Returns the length of the remaining input in bytes without consuming it.
+/// Never fails.
+///
+/// ```rust
+/// use komb::{Parser, string::rest_len};
+///
+/// assert_eq!(Ok((6, "héllo")), rest_len.parse("héllo"));
+/// ```
+pub fn rest_len(input: &str) -> PResult<&str, usize, Error<'_>> {
+	Ok((input.len(), input))
+}
+
+/// Consumes the whole input and returns it with the leading and trailing
+/// whitespace removed and each inner run of whitespace replaced by a single
+/// space, like the normalization of XML attribute values.  Never fails.
+///
+/// To normalize only a part of the input, call it on the output of another
+/// parser, like a quoted string.
+///
+/// ```rust
+/// use komb::{Parser, string::collapse_ws};
+///
+/// assert_eq!(Ok(("a b c".to_string(), "")), collapse_ws.parse("  a   b\tc  "));
+/// assert_eq!(Ok((String::new(), "")), collapse_ws.parse(" \n "));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn collapse_ws(input: &str) -> PResult<&str, String, Error<'_>> {
+	let mut output = String::with_capacity(input.len());
+
+	for word in input.split_whitespace() {
+		if !output.is_empty() {
+			output.push(' ');
+		}
+		output.push_str(word);
+	}
+
+	Ok((output, &input[input.len()..]))
+}
+
+/// Matches a single shell-style word and returns it unquoted.
+///
+/// A word is made of adjacent segments, which are concatenated like in a
+/// shell: runs of bare non-whitespace characters, single-quoted strings
+/// without escapes, and double-quoted strings where `\"` and `\\` are
+/// escapes.  Other backslashes are kept as they are.
+///
+/// An unterminated quote returns [`Error::End`].  If the input starts with
+/// whitespace, [`Error::Unmatched`] is returned.
+///
+/// ```rust
+/// use komb::{Parser, string::{shell_word, whitespace}};
+///
+/// let p = shell_word().separated_by(whitespace);
+///
+/// let (words, _) = p.parse(r#"echo 'a b' "x\"y" a"b"c"#).unwrap();
+/// assert_eq!(vec!["echo", "a b", "x\"y", "abc"], words);
+/// assert!(shell_word().parse("'open").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn shell_word<'a>() -> impl Parser<'a, &'a str, String, Error<'a>> {
+	move |input: &'a str| {
+		let mut output = String::new();
+		let mut rest = input;
+
+		while let Some(ch) = rest.chars().next() {
+			if ch.is_whitespace() {
+				break;
+			}
+
+			let after = &rest[ch.len_utf8()..];
+			rest = match ch {
+				'\'' => {
+					let Some(end) = after.find('\'') else {
+						return Err(Error::end(after));
+					};
+					output.push_str(&after[..end]);
+					&after[end + 1..]
+				}
+				'"' => double_quoted(after, &mut output)?,
+				_ => {
+					output.push(ch);
+					after
+				}
+			};
+		}
+
+		if rest.len() == input.len() {
+			return match input.chars().next() {
+				Some(ch) => Err(Error::unmatched(
+					&input[..ch.len_utf8()],
+				)),
+				None => Err(Error::end(input)),
+			};
+		}
+
+		Ok((output, rest))
+	}
+}
+
+/// Unescapes the contents of a double-quoted [`shell_word`] segment into
+/// `output` and returns the input after the closing quote.
+#[cfg(feature = "alloc")]
+fn double_quoted<'a>(
+	input: &'a str,
+	output: &mut String,
+) -> Result<&'a str, Error<'a>> {
+	let mut chars = input.char_indices();
+
+	while let Some((i, ch)) = chars.next() {
+		match ch {
+			'"' => return Ok(&input[i + 1..]),
+			'\\' => match input[i + 1..].chars().next() {
+				Some(escaped @ ('"' | '\\')) => {
+					chars.next();
+					output.push(escaped);
+				}
+				_ => output.push('\\'),
+			},
+			_ => output.push(ch),
+		}
+	}
+
+	Err(Error::end(input))
+}
+
+/// Splits a whole command line into words like a shell, see [`shell_word`].
+///
+/// The words are separated by whitespace, which is also skipped at the start
+/// and the end.  An unterminated quote returns [`Error::End`].
+///
+/// ```rust
+/// use komb::{Parser, string::shell_split};
+///
+/// let (argv, _) = shell_split.parse(r#"  cp "my file" 'a b'c  "#).unwrap();
+/// assert_eq!(vec!["cp", "my file", "a bc"], argv);
+/// assert_eq!(Ok((vec![], "")), shell_split.parse(" "));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn shell_split(input: &str) -> PResult<&str, Vec<String>, Error<'_>> {
+	let mut words = Vec::new();
+	let (_, mut rest) = or0(whitespace).parse(input)?;
+
+	while !rest.is_empty() {
+		let (word, after) = shell_word().parse(rest)?;
+		words.push(word);
+		(_, rest) = or0(whitespace).parse(after)?;
+	}
+
+	Ok((words, rest))
+}
+
+/// Runs the `parser` and requires it to consume the whole input.
+///
+/// If there is input left after the `parser`, [`Error::NotEnd`] is returned.
+/// Its span points to where the trailing input begins.
+///
+/// ```rust
+/// use komb::{Parser, string::{all_consuming, alphabetic, Error}};
+///
+/// let p = all_consuming(alphabetic);
+///
+/// assert_eq!(Ok(("hello", "")), p.parse("hello"));
+///
+/// let input = "hello, world";
+/// let Err(Error::NotEnd { span }) = p.parse(input) else {
+///     panic!();
+/// };
+/// assert_eq!(",", span);
+/// assert_eq!(5, span.as_ptr() as usize - input.as_ptr() as usize);
+/// ```
+pub fn all_consuming<'a, O>(
+	parser: impl Parser<'a, &'a str, O, Error<'a>> + 'a,
+) -> impl Parser<'a, &'a str, O, Error<'a>>
+where
+	O: 'a,
+{
+	parser.before(eof)
+}
+
+/// Fails with [`Error::Message`] without running `parser` if the input is
+/// longer than `max_bytes`.
+///
+/// This guards parsers of untrusted input against doing a lot of work on
+/// oversized input.  It should wrap the top-level parser, since the inner
+/// parsers only see the rest of the input.
+///
+/// ```rust
+/// use core::cell::Cell;
+/// use komb::{Parser, string::{alphabetic, bounded_input}};
+///
+/// let runs = Cell::new(0);
+/// let p = bounded_input(8, |input| {
+///     runs.set(runs.get() + 1);
+///     alphabetic.parse(input)
+/// });
+///
+/// assert_eq!(Ok(("short", "")), p.parse("short"));
+/// assert!(p.parse("much too long").is_err());
+/// assert_eq!(1, runs.get());
+/// ```
+pub fn bounded_input<'a, O>(
+	max_bytes: usize,
+	parser: impl Parser<'a, &'a str, O, Error<'a>>,
+) -> impl Parser<'a, &'a str, O, Error<'a>> {
+	move |input: &'a str| {
+		if input.len() > max_bytes {
+			return Err(Error::message(input, "input is too long"));
+		}
+
+		parser.parse(input)
+	}
+}
+
+/// Takes exactly `length` characters (not bytes) from the input.  Returns
+/// [`Error::End`] if the string isn't long enough.
+pub fn take<'a>(length: usize) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	move |input: &'a str| {
+		if length == 0 {
+			return Ok(input.split_at(0));
+		}
+
+		let mut current_length = 0;
+		for (i, ch) in input.char_indices() {
+			current_length += 1;
+			if current_length == length {
+				let split = i + ch.len_utf8();
+				return Ok((&input[..split], &input[split..]));
+			}
+		}
+
+		Err(Error::end(input))
+	}
+}
+
+/// Takes characters, see [`length_data`][combinator::length_data].
+impl<'a> TakeUnits<Error<'a>> for &'a str {
+	fn take_units(self, length: usize) -> PResult<Self, Self, Error<'a>> {
+		take(length).parse(self)
+	}
+}
+
+/// Cuts off a prefix of a string for whose characters the predicate `f` returns
+/// `true`.
+///
+/// ```rust
+/// use komb::{Parser, string::take_while};
+///
+/// let p = take_while(|ch| ch == '0' || ch == '1');
+///
+/// assert_eq!(Ok(("01010", "rest")), p.parse("01010rest"));
+/// assert!(p.parse("other").is_err());
+/// ```
+pub fn take_while<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
 where
 	F: Fn(char) -> bool + 'a,
 {
 	move |input: &'a str| {
-		let Some(ch) = input.chars().next() else {
-			return Err(Error::end(input));
-		};
+		let mut index = 0;
+		let mut at_least_one = false;
 
-		let lenght = ch.len_utf8();
-		if f(ch) {
-			Ok((&input[..lenght], &input[lenght..]))
+		for (i, char) in input.char_indices() {
+			if !f(char) {
+				return if at_least_one {
+					Ok((&input[..i], &input[i..]))
+				} else {
+					Err(Error::end(input))
+				};
+			}
+			index = i + char.len_utf8();
+			at_least_one = true;
+		}
+
+		if at_least_one {
+			Ok((&input[..index], &input[index..]))
 		} else {
-			Err(Error::unmatched(&input[..lenght]))
+			Err(Error::end(input))
 		}
 	}
 }
 
-/// Returns whatever char is first in input.  It can return [`Error::End`]
-/// if the input is empty.
-pub fn any_char(input: &str) -> PResult<&str, &str, Error> {
-	char(|_| true).parse(input)
+/// Cuts off a prefix of at least `min` and at most `max` characters for which
+/// the predicate `f` returns `true`.
+///
+/// Stops after `max` characters even if the next one satisfies the predicate.
+/// If fewer than `min` characters match, returns [`Error::End`] when the input
+/// ran out and [`Error::Unmatched`] otherwise.  With `min == 0` the parser
+/// never fails and may return an empty slice, which is always the case for
+/// `max == 0`.
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`.
+///
+/// ```rust
+/// use komb::{Parser, string::take_while_m_n};
+///
+/// let hex_byte = ('%', take_while_m_n(2, 2, |c| c.is_ascii_hexdigit()));
+///
+/// assert_eq!(Ok((("%", "2F"), "3")), hex_byte.parse("%2F3"));
+/// assert!(hex_byte.parse("%2G").is_err());
+/// assert!(hex_byte.parse("%2").is_err());
+///
+/// let octal = take_while_m_n(1, 3, |c| ('0'..='7').contains(&c));
+/// assert_eq!(Ok(("012", "3")), octal.parse("0123"));
+/// ```
+pub fn take_while_m_n<'a, F>(
+	min: usize,
+	max: usize,
+	f: F,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+where
+	F: Fn(char) -> bool + 'a,
+{
+	assert!(min <= max);
+
+	move |input: &'a str| {
+		let mut count = 0;
+		let mut index = 0;
+
+		for char in input.chars().take(max) {
+			if !f(char) {
+				break;
+			}
+			count += 1;
+			index += char.len_utf8();
+		}
+
+		if count >= min {
+			Ok((&input[..index], &input[index..]))
+		} else if index == input.len() {
+			Err(Error::end(input))
+		} else {
+			let next = input[index..].chars().next().unwrap();
+			Err(Error::unmatched(
+				&input[index..index + next.len_utf8()],
+			))
+		}
+	}
+}
+
+/// Matches a prefix until the first character which satisfies the predicate.
+pub fn take_until<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+where
+	F: Fn(char) -> bool + 'a,
+{
+	take_while(move |c| !f(c))
+}
+
+/// Matches the characters in `chars`.
+pub fn one_of<'a, 'c: 'a>(
+	chars: &'c [char],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	take_while(move |c| chars.contains(&c))
+}
+
+/// Matches the characters not in `chars`.
+pub fn none_of<'a, 'c: 'a>(
+	chars: &'c [char],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	take_until(move |c| chars.contains(&c))
+}
+
+/// Matches Unicode whitespace.
+///
+/// Uses [`char::is_whitespace`].
+pub fn whitespace(input: &str) -> PResult<&str, &str, Error<'_>> {
+	take_while(|c| c.is_whitespace()).parse(input)
+}
+
+/// Matches alphabetic characters.
+///
+/// Uses [`char::is_alphabetic`].
+pub fn alphanumeric(input: &str) -> PResult<&str, &str, Error<'_>> {
+	take_while(|c| c.is_alphanumeric()).parse(input)
+}
+
+/// Matches alphanumeric characters.
+///
+/// Uses [`char::is_alphanumeric`].
+///
+/// ```rust
+/// use komb::{Parser, string::alphabetic};
+///
+/// let p = alphabetic;
+///
+/// assert_eq!(Ok(("abcXYZ", " rest")), p.parse("abcXYZ rest"));
+/// assert!(p.parse("_ident").is_err());
+/// ```
+pub fn alphabetic(input: &str) -> PResult<&str, &str, Error<'_>> {
+	take_while(|c| c.is_alphabetic()).parse(input)
+}
+
+/// Parses a `body` surrounded by optional whitespace and followed by a
+/// `terminator`, returning the output of `body`.
+///
+/// Whitespace after the `terminator` isn't consumed.
+///
+/// ```rust
+/// use komb::{Parser, string::{alphabetic, or0, statement, u32, whitespace}};
+///
+/// let assignment = (alphabetic, or0(whitespace), "=", or0(whitespace), u32)
+///     .map_out(|(name, _, _, _, value)| (name, value));
+/// let p = statement(assignment, ";");
+///
+/// assert_eq!(Ok((("x", 1), "  ")), p.parse("  x = 1 ;  "));
+/// assert_eq!(Ok((("y", 2), "")), p.parse("y=2;"));
+/// assert!(p.parse("x = 1").is_err());
+/// ```
+pub fn statement<'a, O>(
+	body: impl Parser<'a, &'a str, O, Error<'a>> + 'a,
+	terminator: &'static str,
+) -> impl Parser<'a, &'a str, O, Error<'a>>
+where
+	O: 'a,
+{
+	delimited(or0(whitespace), body, (or0(whitespace), terminator))
+}
+
+/// Parses `name`, then `sep`, then `value`, allowing whitespace around the
+/// separator and before the name.
+///
+/// An error returned by `value` is wrapped in [`Error::Field`], so that
+/// it points to the field which was being parsed.
+///
+/// ```rust
+/// use komb::{Parser, string::{field, u32}};
+///
+/// let p = field("port", "=", u32);
+///
+/// assert_eq!(Ok((8080, "")), p.parse("port = 8080"));
+/// assert_eq!(Ok((22, ";")), p.parse("port=22;"));
+///
+/// let error = p.parse("port = http").unwrap_err();
+/// assert!(error.to_string().contains("port"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn field<'a, O>(
+	name: &'static str,
+	sep: &'static str,
+	value: impl Parser<'a, &'a str, O, Error<'a>> + 'a,
+) -> impl Parser<'a, &'a str, O, Error<'a>>
+where
+	O: 'a,
+{
+	let value = value.map_err(move |error| Error::Field {
+		name,
+		error: Box::new(error),
+	});
+
+	preceded(
+		(or0(whitespace), name, or0(whitespace), sep, or0(whitespace)),
+		value,
+	)
+}
+
+// Character combinators
+
+/// Returns the first character in input if it satisfies the predicate.
+///
+/// If the predicate fails, [`Error::Unmatched`] is returned.  If the string is
+/// empty, [`Error::End`] is returned.
+///
+/// This function returns a borrowed string slice `&str` to preserve the
+/// location of the character.
+///
+/// ```rust
+/// use komb::{Parser, string::char};
+///
+/// let p = char(|ch| ch == '1' || ch == 'a');
+///
+/// assert_eq!(Ok(("1", "rest")), p.parse("1rest"));
+/// assert_eq!(Ok(("a", "1")), p.parse("a1"));
+/// assert!(p.parse("x").is_err());
+/// ```
+pub fn char<'a, F>(f: F) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+where
+	F: Fn(char) -> bool + 'a,
+{
+	move |input: &'a str| {
+		let Some(ch) = input.chars().next() else {
+			return Err(Error::end(input));
+		};
+
+		let lenght = ch.len_utf8();
+		if f(ch) {
+			Ok((&input[..lenght], &input[lenght..]))
+		} else {
+			Err(Error::unmatched(&input[..lenght]))
+		}
+	}
+}
+
+/// Returns whatever char is first in input.  It can return [`Error::End`]
+/// if the input is empty.
+pub fn any_char(input: &str) -> PResult<&str, &str, Error<'_>> {
+	char(|_| true).parse(input)
+}
+
+/// Returns the first input char if it's one of `chars`.
+pub fn one_of_char<'a, 'c: 'a>(
+	chars: &'c [char],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	char(|ch| chars.contains(&ch))
+}
+
+/// Returns the first input char if it's *not* one of `chars`.
+pub fn none_of_char<'a, 'c: 'a>(
+	chars: &'c [char],
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
+	char(|ch| !chars.contains(&ch))
+}
+
+/// A character class with a precomputed lookup table for ASCII.
+///
+/// The predicate is evaluated for all 128 ASCII characters once, when the
+/// table is created.  Checking an ASCII character is then a single bit test,
+/// while the rest of Unicode falls back to calling the predicate.  This pays
+/// off for expensive predicates on ASCII-dominant input.
+///
+/// ```rust
+/// use komb::string::CharTable;
+///
+/// let table = CharTable::new(|ch| ch.is_ascii_hexdigit() || ch == 'ж');
+///
+/// assert!(table.contains('f'));
+/// assert!(table.contains('ж'));
+/// assert!(!table.contains('g'));
+/// ```
+pub struct CharTable<F> {
+	ascii: u128,
+	predicate: F,
+}
+
+impl<F> CharTable<F>
+where
+	F: Fn(char) -> bool,
+{
+	/// Creates a new table, evaluating `predicate` for every ASCII
+	/// character.
+	pub fn new(predicate: F) -> Self {
+		let mut ascii = 0;
+		for byte in 0..128u8 {
+			if predicate(byte as char) {
+				ascii |= 1 << byte;
+			}
+		}
+
+		CharTable { ascii, predicate }
+	}
+
+	/// Returns `true` if `ch` belongs to the character class.
+	pub fn contains(&self, ch: char) -> bool {
+		if ch.is_ascii() {
+			self.ascii & (1 << ch as u32) != 0
+		} else {
+			(self.predicate)(ch)
+		}
+	}
+}
+
+/// Returns the first character in input if it is contained in the `table`.
+///
+/// This is a faster version of [`char`][fn@char] for ASCII input.  The errors
+/// are the same.
+///
+/// ```rust
+/// use komb::{Parser, string::{CharTable, char_table}};
+///
+/// let p = char_table(CharTable::new(|ch| ch == '_' || ch.is_alphabetic()));
+///
+/// assert_eq!(Ok(("_", "ident")), p.parse("_ident"));
+/// assert!(p.parse("1").is_err());
+/// ```
+pub fn char_table<'a, F>(
+	table: CharTable<F>,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+where
+	F: Fn(char) -> bool + 'a,
+{
+	char(move |ch| table.contains(ch))
+}
+
+/// Matches an identifier: one character which satisfies `is_start`, followed
+/// by any number of characters which satisfy `is_continue`.
+///
+/// ```rust
+/// use komb::{Parser, string::identifier};
+///
+/// let p = identifier(|ch| ch == '@', char::is_alphanumeric);
+///
+/// assert_eq!(Ok(("@user1", " rest")), p.parse("@user1 rest"));
+/// assert_eq!(Ok(("@", "!")), p.parse("@!"));
+/// assert!(p.parse("user").is_err());
+/// ```
+pub fn identifier<'a, F, G>(
+	is_start: F,
+	is_continue: G,
+) -> impl Parser<'a, &'a str, &'a str, Error<'a>>
+where
+	F: Fn(char) -> bool + 'a,
+	G: Fn(char) -> bool + 'a,
+{
+	consume((char(is_start), or0(take_while(is_continue))))
+}
+
+/// Matches an ASCII identifier, which starts with a letter or an underscore,
+/// followed by letters, digits and underscores.
+///
+/// ```rust
+/// use komb::{Parser, string::ident_ascii};
+///
+/// assert_eq!(Ok(("_snake_case2", "()")), ident_ascii.parse("_snake_case2()"));
+/// assert!(ident_ascii.parse("2d").is_err());
+/// assert!(ident_ascii.parse("ж").is_err());
+/// ```
+pub fn ident_ascii(input: &str) -> PResult<&str, &str, Error<'_>> {
+	identifier(
+		|ch| ch.is_ascii_alphabetic() || ch == '_',
+		|ch| ch.is_ascii_alphanumeric() || ch == '_',
+	)
+	.parse(input)
+}
+
+/// Matches digits in a radix.
+///
+/// Uses [`char::is_digit`] underneath.
+///
+/// ```rust
+/// use komb::{Parser, string::digits};
+///
+/// let p = digits::<16>;
+///
+/// assert_eq!(Ok(("deadbeef", "rest")), p.parse("deadbeefrest"));
+/// assert!(p.parse("").is_err());
+/// ```
+pub fn digits<const R: u32>(input: &str) -> PResult<&str, &str, Error<'_>> {
+	take_while(move |c| c.is_digit(R)).parse(input)
+}
+
+macro_rules! impl_parse_uint {
+	($type:ident) => {
+		#[doc=concat!("Parses a decimal [`", stringify!($type), "`][prim@", stringify!($type), "].")]
+		///
+		/// Plus or minus signs aren't accepted.
+		pub fn $type(input: &str) -> PResult<&str, $type, Error<'_>> {
+			let (s, rest) = digits::<10>.parse(input)?;
+			let out = s.parse().map_err(|error| {
+				Error::ParseInt { error, span: s }
+			})?;
+
+			Ok((out, rest))
+		}
+	};
+}
+
+impl_parse_uint!(u8);
+impl_parse_uint!(u16);
+impl_parse_uint!(u32);
+impl_parse_uint!(u64);
+impl_parse_uint!(usize);
+
+/// Parses a decimal unsigned integer whose digits may be grouped with `sep`,
+/// like `1_000_000` or `1,000,000`.
+///
+/// The separator may only appear between digits, so leading, trailing and
+/// doubled separators are rejected.  On overflow, [`Error::ParseInt`] points
+/// to the whole number, separators included.
+///
+/// ```rust
+/// use komb::{Parser, string::uint_grouped};
+///
+/// let p = uint_grouped::<u32>('_');
+///
+/// assert_eq!(Ok((1_000_000, " rest")), p.parse("1_000_000 rest"));
+/// assert_eq!(Ok((42, "")), p.parse("42"));
+/// assert!(p.parse("_1").is_err());
+/// assert!(p.parse("1_").is_err());
+/// assert!(p.parse("1__0").is_err());
+///
+/// let p = uint_grouped::<u8>(',');
+/// assert_eq!(Ok((255, "")), p.parse("2,55"));
+/// assert!(p.parse("2,56").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn uint_grouped<'a, T>(sep: char) -> impl Parser<'a, &'a str, T, Error<'a>>
+where
+	T: core::str::FromStr<Err = ParseIntError>,
+{
+	move |input: &'a str| {
+		let group = (sep, digits::<10>);
+		let (span, rest) =
+			consume((digits::<10>, many0(group))).parse(input)?;
+
+		if rest.starts_with(sep) {
+			let span = &rest[..sep.len_utf8()];
+			return Err(Error::Unmatched { span });
+		}
+
+		let digits: String =
+			span.chars().filter(|&ch| ch != sep).collect();
+		let out = digits
+			.parse()
+			.map_err(|error| Error::ParseInt { error, span })?;
+
+		Ok((out, rest))
+	}
+}
+
+/// Matches an optional `+` or `-` sign and returns `1` or `-1`.
+///
+/// Without a sign nothing is consumed and `1` is returned, so this never
+/// fails.  The numeric parsers in this module use it for their signs.
+///
+/// ```rust
+/// use komb::{Parser, string::sign};
+///
+/// assert_eq!(Ok((-1, "5")), sign.parse("-5"));
+/// assert_eq!(Ok((1, "5")), sign.parse("+5"));
+/// assert_eq!(Ok((1, "5")), sign.parse("5"));
+/// assert_eq!(Ok((1, "")), sign.parse(""));
+/// ```
+pub fn sign(input: &str) -> PResult<&str, i8, Error<'_>> {
+	match input.chars().next() {
+		Some('-') => Ok((-1, &input[1..])),
+		Some('+') => Ok((1, &input[1..])),
+		_ => Ok((1, input)),
+	}
+}
+
+macro_rules! impl_parse_sint {
+	($type:ident) => {
+		#[doc=concat!("Parses a decimal [`", stringify!($type), "`][prim@", stringify!($type), "].")]
+		///
+		/// ```rust
+		/// use komb::Parser;
+		#[doc=concat!("use komb::string::", stringify!($type), ";")]
+		///
+		#[doc=concat!("assert_eq!(Ok((3, \"\")), ", stringify!($type), ".parse(\"3\"));")]
+		#[doc=concat!("assert_eq!(Ok((-1, \"\")), ", stringify!($type), ".parse(\"-1\"));")]
+		#[doc=concat!("assert_eq!(Ok((4, \"\")), ", stringify!($type), ".parse(\"+4\"));")]
+		/// ```
+		pub fn $type(input: &str) -> PResult<&str, $type, Error<'_>> {
+			let (s, rest) =
+				consume((sign, digits::<10>)).parse(input)?;
+			let out = s.parse().map_err(|error| {
+				Error::ParseInt { error, span: s }
+			})?;
+
+			Ok((out, rest))
+		}
+	};
+}
+
+impl_parse_sint!(i8);
+impl_parse_sint!(i16);
+impl_parse_sint!(i32);
+impl_parse_sint!(i64);
+impl_parse_sint!(isize);
+
+/// Matches the digits in radix `R` after an optional `prefix`, and returns
+/// them without the prefix.
+///
+/// A prefix of another radix is rejected, unless it's a valid digit in `R`
+/// (like `0b` in hexadecimal).
+fn prefixed_digits<'a, const R: u32>(
+	prefix: &'static str,
+	input: &'a str,
+) -> PResult<&'a str, &'a str, Error<'a>> {
+	let (found, rest) = optional(anycase(prefix)).parse(input)?;
+
+	if found.is_none() {
+		for other in ["0x", "0o", "0b"] {
+			let letter = other.as_bytes()[1] as char;
+			if other != prefix
+				&& !letter.is_digit(R) && anycase(other)
+				.parse(input)
+				.is_ok()
+			{
+				return Err(Error::unmatched(&input[..2]));
+			}
+		}
+	}
+
+	digits::<R>.parse(rest)
+}
+
+/// Parses an unsigned integer in radix `R` after an optional `prefix`.
+fn uint_radix<'a, const R: u32>(
+	prefix: &'static str,
+	input: &'a str,
+) -> PResult<&'a str, u64, Error<'a>> {
+	let (digits, rest) = prefixed_digits::<R>(prefix, input)?;
+	let span = input.consumed(rest);
+	let out = u64::from_str_radix(digits, R)
+		.map_err(|error| Error::ParseInt { error, span })?;
+
+	Ok((out, rest))
+}
+
+/// Parses a signed integer in radix `R` with the optional `prefix` after the
+/// sign.
+fn sint_radix<'a, const R: u32>(
+	prefix: &'static str,
+	input: &'a str,
+) -> PResult<&'a str, i64, Error<'a>> {
+	let (sign, rest) = sign(input)?;
+	let (digits, rest) = prefixed_digits::<R>(prefix, rest)?;
+	let span = input.consumed(rest);
+
+	let out = match (sign < 0, u64::from_str_radix(digits, R)) {
+		(false, _) => i64::from_str_radix(digits, R),
+		// `i64::MIN` is the only magnitude which doesn't fit into `i64`,
+		// and it negates to itself
+		(true, Ok(magnitude))
+			if magnitude <= i64::MIN.unsigned_abs() =>
+		{
+			Ok((magnitude as i64).wrapping_neg())
+		}
+		// only the standard library can create a `ParseIntError`
+		(true, _) => "-9223372036854775809".parse::<i64>(),
+	};
+	let out = out.map_err(|error| Error::ParseInt { error, span })?;
+
+	Ok((out, rest))
+}
+
+macro_rules! impl_parse_radix {
+	($unsigned:ident, $signed:ident, $radix:literal, $prefix:literal, $example:literal, $value:literal) => {
+		#[doc=concat!("Parses a [`u64`][prim@u64] in radix ", stringify!($radix), " with an optional `", $prefix, "` prefix.")]
+		///
+		/// The prefix is case-insensitive.  Signs aren't accepted.
+		///
+		/// ```rust
+		/// use komb::Parser;
+		#[doc=concat!("use komb::string::", stringify!($unsigned), ";")]
+		///
+		#[doc=concat!("assert_eq!(Ok((", $value, ", \"\")), ", stringify!($unsigned), ".parse(\"", $prefix, $example, "\"));")]
+		#[doc=concat!("assert_eq!(Ok((", $value, ", \"\")), ", stringify!($unsigned), ".parse(\"", $example, "\"));")]
+		/// ```
+		pub fn $unsigned(input: &str) -> PResult<&str, u64, Error<'_>> {
+			uint_radix::<$radix>($prefix, input)
+		}
+
+		#[doc=concat!("Parses an [`i64`][prim@i64] in radix ", stringify!($radix), " with an optional `", $prefix, "` prefix after the sign.")]
+		///
+		/// ```rust
+		/// use komb::Parser;
+		#[doc=concat!("use komb::string::", stringify!($signed), ";")]
+		///
+		#[doc=concat!("assert_eq!(Ok((-", $value, ", \"\")), ", stringify!($signed), ".parse(\"-", $prefix, $example, "\"));")]
+		#[doc=concat!("assert_eq!(Ok((", $value, ", \"\")), ", stringify!($signed), ".parse(\"+", $example, "\"));")]
+		/// ```
+		pub fn $signed(input: &str) -> PResult<&str, i64, Error<'_>> {
+			sint_radix::<$radix>($prefix, input)
+		}
+	};
+}
+
+impl_parse_radix!(hex_u64, hex_i64, 16, "0x", "DEAD", "0xDEAD");
+impl_parse_radix!(oct_u64, oct_i64, 8, "0o", "755", "0o755");
+impl_parse_radix!(bin_u64, bin_i64, 2, "0b", "101", "0b101");
+
+/// Parses an [`i64`][prim@i64] whose radix is given by its prefix: `0x` for
+/// hexadecimal, `0o` for octal, `0b` for binary, and none for decimal.
+///
+/// The optional sign goes before the prefix, which is case-insensitive.
+/// Digits which don't belong to the radix are rejected.
+///
+/// ```rust
+/// use komb::{Parser, string::int_auto_radix};
+///
+/// assert_eq!(Ok((255, "")), int_auto_radix.parse("0xFF"));
+/// assert_eq!(Ok((5, "")), int_auto_radix.parse("0b101"));
+/// assert_eq!(Ok((15, "")), int_auto_radix.parse("0o17"));
+/// assert_eq!(Ok((-42, "")), int_auto_radix.parse("-42"));
+/// assert_eq!(Ok((-16, ";")), int_auto_radix.parse("-0x10;"));
+/// assert!(int_auto_radix.parse("0b2").is_err());
+/// ```
+pub fn int_auto_radix(input: &str) -> PResult<&str, i64, Error<'_>> {
+	type IntParser = fn(&str) -> PResult<&str, i64, Error<'_>>;
+
+	let (_, unsigned) = sign(input)?;
+	let has_prefix = |prefix| anycase(prefix).parse(unsigned).is_ok();
+
+	let parser: IntParser = if has_prefix("0x") {
+		hex_i64
+	} else if has_prefix("0o") {
+		oct_i64
+	} else if has_prefix("0b") {
+		bin_i64
+	} else {
+		i64
+	};
+
+	parser(input)
+}
+
+/// Matches exactly `bits` binary digits and returns them as an unsigned
+/// number.
+fn bit_run(bits: u32, input: &str) -> PResult<&str, u64, Error<'_>> {
+	let (span, rest) = digits::<2>.parse(input)?;
+	if span.len() != bits as usize {
+		return Err(Error::Message {
+			message: "wrong number of bits",
+			span,
+		});
+	}
+
+	let out = u64::from_str_radix(span, 2)
+		.map_err(|error| Error::ParseInt { error, span })?;
+	Ok((out, rest))
+}
+
+/// Parses exactly `bits` binary digits as a two's complement number.
+///
+/// A run of binary digits of another length fails with
+/// [`Error::Message`].
+///
+/// # Panics
+///
+/// Panics if `bits` isn't between 1 and 64.
+///
+/// ```rust
+/// use komb::{Parser, string::{bin_u64, twos_complement}};
+///
+/// let p = twos_complement(4);
+///
+/// assert_eq!(Ok((-1, "")), p.parse("1111"));
+/// assert_eq!(Ok((15, "")), bin_u64.parse("1111"));
+/// assert_eq!(Ok((-8, "")), p.parse("1000"));
+/// assert_eq!(Ok((7, "")), p.parse("0111"));
+/// assert!(p.parse("111").is_err());
+/// assert!(p.parse("11111").is_err());
+/// ```
+pub fn twos_complement<'a>(
+	bits: u32,
+) -> impl Parser<'a, &'a str, i64, Error<'a>> {
+	assert!((1..=64).contains(&bits), "bits must be between 1 and 64");
+
+	move |input: &'a str| {
+		let (value, rest) = bit_run(bits, input)?;
+		let shift = 64 - bits;
+		Ok((((value << shift) as i64) >> shift, rest))
+	}
+}
+
+/// Parses exactly `bits` binary digits as a sign-magnitude number: the
+/// first digit is the sign and the rest is the absolute value.
+///
+/// Both `0000` and `1000` are zero.  A run of binary digits of another length
+/// fails with [`Error::Message`].
+///
+/// # Panics
+///
+/// Panics if `bits` isn't between 1 and 64.
+///
+/// ```rust
+/// use komb::{Parser, string::sign_magnitude};
+///
+/// let p = sign_magnitude(4);
+///
+/// assert_eq!(Ok((-7, "")), p.parse("1111"));
+/// assert_eq!(Ok((5, "")), p.parse("0101"));
+/// assert_eq!(Ok((0, "")), p.parse("1000"));
+/// ```
+pub fn sign_magnitude<'a>(
+	bits: u32,
+) -> impl Parser<'a, &'a str, i64, Error<'a>> {
+	assert!((1..=64).contains(&bits), "bits must be between 1 and 64");
+
+	move |input: &'a str| {
+		let (value, rest) = bit_run(bits, input)?;
+		let sign = 1 << (bits - 1);
+		let magnitude = (value & !sign) as i64;
+
+		let out = if value & sign == 0 {
+			magnitude
+		} else {
+			-magnitude
+		};
+		Ok((out, rest))
+	}
+}
+
+macro_rules! impl_parse_float {
+	($type:ident) => {
+		#[doc=concat!("Parses a [`", stringify!($type), "`][prim@", stringify!($type), "].")]
+		///
+		/// This function uses Rust-style grammar.  See
+		/// [`from_str`][prim@f64#method.from_str] for the description.
+		///
+		/// ```rust
+		/// use komb::Parser;
+		#[doc=concat!("use komb::string::", stringify!($type), ";")]
+		///
+		#[doc=concat!("assert_eq!(Ok((3.14, \"\")), ", stringify!($type), ".parse(\"3.14\"));")]
+		#[doc=concat!("assert_eq!(Ok((-3.14, \"\")), ", stringify!($type), ".parse(\"-3.14\"));")]
+		#[doc=concat!("assert_eq!(Ok((2.5E10, \"\")), ", stringify!($type), ".parse(\"2.5E10\"));")]
+		#[doc=concat!("assert_eq!(Ok((2.5E-10, \"\")), ", stringify!($type), ".parse(\"2.5E-10\"));")]
+		#[doc=concat!("assert_eq!(Ok((5.0, \"\")), ", stringify!($type), ".parse(\"5.\"));")]
+		#[doc=concat!("assert_eq!(Ok((0.5, \"\")), ", stringify!($type), ".parse(\".5\"));")]
+		#[doc=concat!("assert_eq!(Ok((", stringify!($type), "::INFINITY, \"\")), ", stringify!($type), ".parse(\"iNf\"));")]
+		#[doc=concat!("assert_eq!(Ok((", stringify!($type), "::NEG_INFINITY, \"\")), ", stringify!($type), ".parse(\"-inF\"));")]
+		/// ```
+		pub fn $type(input: &str) -> PResult<&str, $type, Error<'_>> {
+			use crate::combinator::optional;
+
+			let exp = (anycase("e"), sign, digits::<10>);
+			let number = (
+				choice((
+					(digits::<10>, '.', or0(digits::<10>))
+						.value(()),
+					(or0(digits::<10>), '.', digits::<10>)
+						.value(()),
+					digits::<10>.value(()),
+				)),
+				optional(exp),
+			);
+			let float = (
+				sign,
+				choice((
+					anycase("inf").value(()),
+					anycase("infinity").value(()),
+					anycase("nan").value(()),
+					number.value(()),
+				)),
+			);
+			let s = consume(float);
+
+			let (span, rest) = s.parse(input)?;
+
+			use core::str::FromStr;
+			let out = $type::from_str(span).map_err(|error| {
+				Error::ParseFloat { error, span }
+			})?;
+
+			Ok((out, rest))
+		}
+	};
+}
+
+impl_parse_float!(f32);
+impl_parse_float!(f64);
+
+/// Parses a number in scientific notation and returns the mantissa and the
+/// exponent separately.
+///
+/// Both the mantissa and the exponent may be signed.  Infinities and NaN
+/// aren't accepted.  If there is no exponent, it is zero.
+///
+/// ```rust
+/// use komb::{Parser, string::scientific};
+///
+/// assert_eq!(Ok(((2.5, -10), "")), scientific.parse("2.5E-10"));
+/// assert_eq!(Ok(((-1.0, 3), "")), scientific.parse("-1.e+3"));
+/// assert_eq!(Ok(((42.0, 0), " rest")), scientific.parse("42 rest"));
+/// assert!(scientific.parse("inf").is_err());
+/// ```
+pub fn scientific(input: &str) -> PResult<&str, (f64, i32), Error<'_>> {
+	use core::str::FromStr;
+
+	let number = choice((
+		(digits::<10>, '.', or0(digits::<10>)).value(()),
+		(or0(digits::<10>), '.', digits::<10>).value(()),
+		digits::<10>.value(()),
+	));
+	let mantissa = consume((sign, number));
+
+	let (span, rest) = mantissa.parse(input)?;
+	let mantissa = f64::from_str(span)
+		.map_err(|error| Error::ParseFloat { error, span })?;
+
+	// Only the `e` is optional: an exponent which doesn't fit is an error.
+	let exponent = anycase("e").and_then(consume((sign, digits::<10>)));
+	let (exponent, rest) = optional(exponent).parse(rest)?;
+	let exponent = match exponent {
+		Some(span) => span
+			.parse()
+			.map_err(|error| Error::ParseInt { error, span })?,
+		None => 0,
+	};
+
+	Ok(((mantissa, exponent), rest))
+}
+
+/// Parses a fraction like `-3/4` into the numerator and the denominator.
+///
+/// Only the numerator may be signed.  The fraction isn't reduced.  A zero
+/// denominator is rejected with [`Error::Message`] pointing to it.
+///
+/// ```rust
+/// use komb::{Parser, string::{fraction, Error}};
+///
+/// assert_eq!(Ok(((3, 4), "")), fraction.parse("3/4"));
+/// assert_eq!(Ok(((-1, 2), " cup")), fraction.parse("-1/2 cup"));
+/// assert_eq!(Ok(((2, 4), "")), fraction.parse("2/4"));
+/// assert!(matches!(
+///     fraction.parse("5/0"),
+///     Err(Error::Message { span: "0", .. }),
+/// ));
+/// assert!(fraction.parse("5").is_err());
+/// assert!(fraction.parse("1/-2").is_err());
+/// ```
+pub fn fraction(input: &str) -> PResult<&str, (i64, u64), Error<'_>> {
+	let (numerator, rest) = terminated(i64, '/').parse(input)?;
+	let ((denominator, span), rest) = with_recognized(u64).parse(rest)?;
+
+	if denominator == 0 {
+		return Err(Error::Message {
+			message: "zero denominator",
+			span,
+		});
+	}
+
+	Ok(((numerator, denominator), rest))
+}
+
+/// Parses a Rust-style range like `1..10` or `1..=10` into the start, the end
+/// and whether the end is inclusive.
+///
+/// Both bounds are required and may be signed.
+///
+/// ```rust
+/// use komb::{Parser, string::range_expr};
+///
+/// assert_eq!(Ok(((1, 10, false), "")), range_expr.parse("1..10"));
+/// assert_eq!(Ok(((1, 10, true), "")), range_expr.parse("1..=10"));
+/// assert_eq!(Ok(((-5, -1, false), "]")), range_expr.parse("-5..-1]"));
+/// assert!(range_expr.parse("1..").is_err());
+/// assert!(range_expr.parse("1..=").is_err());
+/// assert!(range_expr.parse("..10").is_err());
+/// ```
+pub fn range_expr(input: &str) -> PResult<&str, (i64, i64, bool), Error<'_>> {
+	let op = choice(("..=".value(true), "..".value(false)));
+
+	(i64, op, i64)
+		.map_out(|(start, inclusive, end)| (start, end, inclusive))
+		.parse(input)
+}
+
+/// Splits a fixed-width record into fields of the given `widths` in bytes and
+/// trims the trailing padding spaces from each field.
+///
+/// A field boundary inside a multi-byte character is moved forward to the end
+/// of that character.  Returns [`Error::End`] if the input is shorter than
+/// the record.
+///
+/// ```rust
+/// use komb::{Parser, string::{fixed_record, Error}};
+///
+/// let p = fixed_record(&[5, 10, 3]);
+///
+/// assert_eq!(
+///     Ok((vec!["42", "Jane Doe", "NY"], "\n")),
+///     p.parse("42   Jane Doe  NY \n"),
+/// );
+/// assert!(matches!(p.parse("42   Jane"), Err(Error::End { .. })));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fixed_record<'a>(
+	widths: &'a [usize],
+) -> impl Parser<'a, &'a str, Vec<&'a str>, Error<'a>> {
+	move |input: &'a str| {
+		let mut fields = Vec::with_capacity(widths.len());
+		let mut rest = input;
+
+		for &width in widths {
+			if rest.len() < width {
+				return Err(Error::end(rest));
+			}
+
+			let mut end = width;
+			while !rest.is_char_boundary(end) {
+				end += 1;
+			}
+
+			let (field, tail) = rest.split_at(end);
+			fields.push(field.trim_end_matches(' '));
+			rest = tail;
+		}
+
+		Ok((fields, rest))
+	}
+}
+
+/// Applies `parser` repeatedly to `input`, recovering from errors, and
+/// returns the outputs of all the successful matches together with all of
+/// the errors.
+///
+/// After a match `parser` is applied again to the rest of the input.  On a
+/// failure the error is recorded, the input is skipped past the first of
+/// the `sync_chars` in the failed segment, and parsing resumes from there.
+/// A match which consumes no input counts as a failure, see
+/// [progress][crate::Sliceable#progress].  Parsing stops at the end of the
+/// input or when there is no sync character left after an error.
+///
+/// ```rust
+/// use komb::{Parser, string::{alphabetic, parse_recovering, u32}};
+///
+/// let statement = (alphabetic, '=', u32, ';')
+///     .map_out(|(name, _, value, _)| (name, value));
+///
+/// let input = "a=1;b=;c=2;d=x;e=3;";
+/// let (outputs, errors) = parse_recovering(statement, &[';'], input);
+///
+/// // the statements around the errors are kept
+/// assert_eq!(vec![("a", 1), ("c", 2), ("e", 3)], outputs);
+/// assert_eq!(2, errors.len());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn parse_recovering<'a, O>(
+	parser: impl Parser<'a, &'a str, O, Error<'a>>,
+	sync_chars: &[char],
+	input: &'a str,
+) -> (Vec<O>, Vec<Error<'a>>) {
+	let mut outputs = Vec::new();
+	let mut errors = Vec::new();
+	let mut rest = input;
+
+	while !rest.is_empty() {
+		let error = match crate::progress(rest, parser.parse(rest)) {
+			Ok((out, tail)) => {
+				outputs.push(out);
+				rest = tail;
+				continue;
+			}
+			Err(error) => error,
+		};
+
+		errors.push(error);
+
+		let Some(sync) = rest.find(sync_chars) else {
+			break;
+		};
+		let length =
+			rest[sync..].chars().next().map_or(0, char::len_utf8);
+		rest = &rest[sync + length..];
+	}
+
+	(outputs, errors)
+}
+
+/// Parses zero or more `item`s separated by any of the `seps` literals.
+///
+/// The separators may be mixed freely, but `seps` must not be empty.  See
+/// [`separated_list0`] for the details.
+///
+/// ```rust
+/// use komb::{Parser, string::{separated_list_multi, u32}};
+///
+/// let p = separated_list_multi(u32, &[",", ";"]);
+///
+/// assert_eq!(Ok((vec![1, 2, 3, 4], "")), p.parse("1,2;3,4"));
+/// assert_eq!(Ok((vec![1], ":2")), p.parse("1:2"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn separated_list_multi<'a, O>(
+	item: impl Parser<'a, &'a str, O, Error<'a>> + 'a,
+	seps: &'a [&'static str],
+) -> impl Parser<'a, &'a str, Vec<O>, Error<'a>>
+where
+	O: 'a,
+{
+	separated_list0(item, choice(seps))
+}
+
+/// Matches two hexadecimal digits and returns their value.
+fn hex_byte(input: &str) -> PResult<&str, u8, Error<'_>> {
+	let hex = || char(|ch| ch.is_ascii_hexdigit());
+	let (span, rest) = consume((hex(), hex())).parse(input)?;
+	let byte = u8::from_str_radix(span, 16)
+		.map_err(|error| Error::ParseInt { error, span })?;
+
+	Ok((byte, rest))
+}
+
+/// Parses a MAC address of six two-digit hexadecimal groups, like
+/// `00:1a:2B:3c:4d:5e`.
+///
+/// The groups are separated either by colons or by dashes, but the
+/// separators can't be mixed.  An address followed by more hexadecimal
+/// digits or groups is rejected.
+///
+/// ```rust
+/// use komb::{Parser, string::mac_address};
+///
+/// let bytes = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+///
+/// assert_eq!(Ok((bytes, " rest")), mac_address.parse("00:1a:2B:3c:4d:5e rest"));
+/// assert_eq!(Ok((bytes, "")), mac_address.parse("00-1a-2b-3c-4d-5e"));
+/// assert!(mac_address.parse("00:1a:2b:3c:4d").is_err());
+/// assert!(mac_address.parse("00:1a:2b:3c:4d:5e:6f").is_err());
+/// assert!(mac_address.parse("00:1a-2b:3c:4d:5e").is_err());
+/// assert!(mac_address.parse("00:1a:2b:3c:4d:5g").is_err());
+/// ```
+pub fn mac_address(input: &str) -> PResult<&str, [u8; 6], Error<'_>> {
+	let (first, mut rest) = hex_byte(input)?;
+	let (sep, _) = choice((":", "-")).parse(rest)?;
+
+	let mut bytes = [first; 6];
+	for byte in &mut bytes[1..] {
+		(*byte, rest) = preceded(sep, hex_byte).parse(rest)?;
+	}
+
+	let trailing = (optional(sep), char(|ch| ch.is_ascii_hexdigit()));
+	if trailing.parse(rest).is_ok() {
+		return Err(Error::message(
+			rest,
+			"too many digits in MAC address",
+		));
+	}
+
+	Ok((bytes, rest))
+}
+
+/// Parses a UUID in the canonical `8-4-4-4-12` hexadecimal form, like
+/// `123e4567-e89b-12d3-a456-426614174000`, and returns its 16 bytes.
+///
+/// The digits may be in either case.  Braces and URN prefixes aren't
+/// accepted, and the hyphens must be exactly between the groups.
+///
+/// ```rust
+/// use komb::{Parser, string::uuid};
+///
+/// let (bytes, rest) = uuid.parse("123e4567-e89b-12D3-a456-426614174000 x").unwrap();
+/// assert_eq!(" x", rest);
+/// assert_eq!(
+///     [0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3,
+///      0xa4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40, 0x00],
+///     bytes,
+/// );
+///
+/// assert!(uuid.parse("123e4567e-89b-12d3-a456-426614174000").is_err());
+/// assert!(uuid.parse("123e4567-e89b-12d3-a456-42661417400").is_err());
+/// assert!(uuid.parse("123e4567-e89b-12d3-a456-4266141740000").is_err());
+/// assert!(uuid.parse("123e4567-e89b-12d3-a456-42661417400g").is_err());
+/// ```
+pub fn uuid(input: &str) -> PResult<&str, [u8; 16], Error<'_>> {
+	let mut bytes = [0; 16];
+	let mut rest = input;
+
+	for (i, byte) in bytes.iter_mut().enumerate() {
+		if matches!(i, 4 | 6 | 8 | 10) {
+			(_, rest) = '-'.parse(rest)?;
+		}
+		(*byte, rest) = hex_byte(rest)?;
+	}
+
+	if char(|ch| ch.is_ascii_hexdigit()).parse(rest).is_ok() {
+		return Err(Error::message(rest, "too many digits in UUID"));
+	}
+
+	Ok((bytes, rest))
+}
+
+/// Parses a color in the `#RRGGBB` or the short `#RGB` form and returns the
+/// red, green and blue components.
+///
+/// In the short form every digit is repeated, so `#f80` is the same as
+/// `#ff8800`.  Runs of hexadecimal digits of any other length are rejected.
+///
+/// ```rust
+/// use komb::{Parser, string::hex_color};
+///
+/// assert_eq!(Ok(((255, 0, 0), "")), hex_color.parse("#f00"));
+/// assert_eq!(Ok(((0x12, 0xab, 0x9f), ";")), hex_color.parse("#12AB9f;"));
+/// assert!(hex_color.parse("#12").is_err());
+/// assert!(hex_color.parse("#1234").is_err());
+/// assert!(hex_color.parse("f00").is_err());
+/// ```
+pub fn hex_color(input: &str) -> PResult<&str, (u8, u8, u8), Error<'_>> {
+	let (_, rest) = '#'.parse(input)?;
+	let (span, rest) =
+		take_while(|ch| ch.is_ascii_hexdigit()).parse(rest)?;
+
+	let color = match span.len() {
+		3 => {
+			let [r, g, b] = [0, 1, 2].map(|i| {
+				let digit = span.as_bytes()[i] as char;
+				digit.to_digit(16).unwrap() as u8 * 0x11
+			});
+			(r, g, b)
+		}
+		6 => (hex_byte, hex_byte, hex_byte).parse(span)?.0,
+		_ => {
+			return Err(Error::message(
+				span,
+				"expected 3 or 6 hexadecimal digits",
+			))
+		}
+	};
+
+	Ok((color, rest))
 }
 
-/// Returns the first input char if it's one of `chars`.
-pub fn one_of_char<'a, 'c: 'a>(
-	chars: &'c [char],
-) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
-	char(|ch| chars.contains(&ch))
-}
+/// Parses an email address and returns the local part and the domain.
+///
+/// Only the commonly accepted subset of the addresses is supported: the
+/// local part consists of dot-separated runs of ASCII alphanumeric characters
+/// and ``!#$%&'*+/=?^_`{|}~-``, and the domain of at least two dot-separated
+/// labels.  A label is ASCII alphanumeric characters and hyphens, but it
+/// can't start or end with a hyphen.  Quoted local parts, comments and IP
+/// address domains aren't accepted.
+///
+/// ```rust
+/// use komb::{Parser, string::email};
+///
+/// assert_eq!(
+///     Ok((("first.last+tag", "mail.example.com"), " rest")),
+///     email.parse("first.last+tag@mail.example.com rest"),
+/// );
+/// assert!(email.parse("user.example.com").is_err());
+/// assert!(email.parse("user@example.com.").is_err());
+/// assert!(email.parse("user@localhost").is_err());
+/// assert!(email.parse(".user@example.com").is_err());
+/// assert!(email.parse("user@-example.com").is_err());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn email(input: &str) -> PResult<&str, (&str, &str), Error<'_>> {
+	let atom = take_while(|ch| {
+		ch.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~-".contains(ch)
+	});
+	let label = verify(
+		take_while(|ch| ch.is_ascii_alphanumeric() || ch == '-'),
+		|label: &&str| !label.starts_with('-') && !label.ends_with('-'),
+	);
 
-/// Returns the first input char if it's *not* one of `chars`.
-pub fn none_of_char<'a, 'c: 'a>(
-	chars: &'c [char],
-) -> impl Parser<'a, &'a str, &'a str, Error<'a>> {
-	char(|ch| !chars.contains(&ch))
+	let (local, rest) = consume(separated_list1(atom, '.')).parse(input)?;
+	let (_, rest) = '@'.parse(rest)?;
+	let ((labels, domain), rest) =
+		with_recognized(separated_list1(label, '.')).parse(rest)?;
+
+	if labels.len() < 2 {
+		return Err(Error::message(
+			rest,
+			"expected a dot in the domain",
+		));
+	}
+	if rest.starts_with(['.', '-']) {
+		return Err(Error::Unmatched { span: &rest[..1] });
+	}
+
+	Ok(((local, domain), rest))
 }
 
-/// Matches digits in a radix.
+/// Parses runs of `normal` characters interleaved with escapes, and returns
+/// them as an owned string with the escapes replaced.
 ///
-/// Uses [`char::is_digit`] underneath.
+/// An escape starts with `control_char`, after which `transform` parses the
+/// rest of it and returns the character which replaces it.  If `transform`
+/// fails, its error is returned.  The parsing stops when neither `normal`
+/// nor `control_char` match, so an empty string is a valid output.
 ///
 /// ```rust
-/// use komb::{Parser, string::digits};
+/// use komb::{Parser, combinator::{choice, delimited}};
+/// use komb::string::{escaped_transform, none_of};
 ///
-/// let p = digits::<16>;
+/// let escape = choice(('n'.value('\n'), '"'.value('"'), '\\'.value('\\')));
+/// let contents = escaped_transform(none_of(&['\\', '"']), '\\', escape);
+/// let p = delimited("\"", contents, "\"");
 ///
-/// assert_eq!(Ok(("deadbeef", "rest")), p.parse("deadbeefrest"));
-/// assert!(p.parse("").is_err());
+/// assert_eq!(Ok(("say \"hi\"\n".to_owned(), "")), p.parse(r#""say \"hi\"\n""#));
+/// assert_eq!(Ok((String::new(), "")), p.parse(r#""""#));
+/// assert!(p.parse(r#""\x""#).is_err());
 /// ```
-pub fn digits<const R: u32>(input: &str) -> PResult<&str, &str, Error> {
-	take_while(move |c| c.is_digit(R)).parse(input)
-}
+#[cfg(feature = "alloc")]
+pub fn escaped_transform<'a>(
+	normal: impl Parser<'a, &'a str, &'a str, Error<'a>> + 'a,
+	control_char: char,
+	transform: impl Parser<'a, &'a str, char, Error<'a>> + 'a,
+) -> impl Parser<'a, &'a str, String, Error<'a>> {
+	move |input: &'a str| {
+		let mut output = String::new();
+		let mut input = input;
 
-macro_rules! impl_parse_uint {
-	($type:ident) => {
-		#[doc=concat!("Parses a decimal [`", stringify!($type), "`][prim@", stringify!($type), "].")]
-		///
-		/// Plus or minus signs aren't accepted.
-		pub fn $type(input: &str) -> PResult<&str, $type, Error> {
-			let (s, rest) = digits::<10>.parse(input)?;
-			let out = s.parse().map_err(|error| {
-				Error::ParseInt { error, span: s }
-			})?;
+		loop {
+			if let Ok((run, rest)) = normal.parse(input) {
+				if rest.len() < input.len() {
+					output.push_str(run);
+					input = rest;
+					continue;
+				}
+			}
 
-			Ok((out, rest))
+			let Ok((_, rest)) = control_char.parse(input) else {
+				break;
+			};
+			let (ch, rest) = transform.parse(rest)?;
+			output.push(ch);
+			input = rest;
 		}
-	};
+
+		Ok((output, input))
+	}
 }
 
-impl_parse_uint!(u8);
-impl_parse_uint!(u16);
-impl_parse_uint!(u32);
-impl_parse_uint!(u64);
-impl_parse_uint!(usize);
+/// Parses a string between two `quote` characters and returns its unescaped
+/// contents.
+///
+/// Inside the string, `escape` followed by `quote` or by another `escape`
+/// stands for that character.  An `escape` before any other character is kept
+/// as is.  If `escape` is the same as `quote`, quotes are escaped by doubling
+/// them, like in SQL and CSV.
+///
+/// Returns [`Error::End`] if the closing quote is missing.
+///
+/// ```rust
+/// use komb::{Parser, string::{quoted_string, Error}};
+///
+/// let p = quoted_string('"', '\\');
+/// assert_eq!(Ok((r#"a "b" \c\n"#.to_owned(), ",")), p.parse(r#""a \"b\" \\c\n","#));
+/// assert!(matches!(p.parse(r#""open"#), Err(Error::End { .. })));
+///
+/// let p = quoted_string('\'', '\'');
+/// assert_eq!(Ok(("it's".to_owned(), " rest")), p.parse("'it''s' rest"));
+/// assert_eq!(Ok((String::new(), "")), p.parse("''"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn quoted_string<'a>(
+	quote: char,
+	escape: char,
+) -> impl Parser<'a, &'a str, String, Error<'a>> {
+	move |input: &'a str| {
+		let (_, input) = quote.parse(input)?;
+		let mut output = String::new();
+		let mut chars = input.char_indices().peekable();
 
-macro_rules! impl_parse_sint {
-	($type:ident) => {
-		#[doc=concat!("Parses a decimal [`", stringify!($type), "`][prim@", stringify!($type), "].")]
-		///
-		/// ```rust
-		/// use komb::Parser;
-		#[doc=concat!("use komb::string::", stringify!($type), ";")]
-		///
-		#[doc=concat!("assert_eq!(Ok((3, \"\")), ", stringify!($type), ".parse(\"3\"));")]
-		#[doc=concat!("assert_eq!(Ok((-1, \"\")), ", stringify!($type), ".parse(\"-1\"));")]
-		#[doc=concat!("assert_eq!(Ok((4, \"\")), ", stringify!($type), ".parse(\"+4\"));")]
-		/// ```
-		pub fn $type(input: &str) -> PResult<&str, $type, Error> {
-			let sign = choice(('+', '-', ""));
-			let (s, rest) =
-				consume((sign, digits::<10>)).parse(input)?;
-			let out = s.parse().map_err(|error| {
-				Error::ParseInt { error, span: s }
-			})?;
+		while let Some((i, ch)) = chars.next() {
+			let next = chars.peek().map(|&(_, next)| next);
 
-			Ok((out, rest))
+			if ch == escape && escape == quote {
+				if next != Some(quote) {
+					return Ok((
+						output,
+						&input[i + ch.len_utf8()..],
+					));
+				}
+				chars.next();
+				output.push(quote);
+			} else if ch == escape {
+				match next {
+					Some(next)
+						if next == quote
+							|| next == escape =>
+					{
+						chars.next();
+						output.push(next);
+					}
+					_ => output.push(ch),
+				}
+			} else if ch == quote {
+				return Ok((
+					output,
+					&input[i + ch.len_utf8()..],
+				));
+			} else {
+				output.push(ch);
+			}
 		}
-	};
+
+		Err(Error::end(input))
+	}
 }
 
-impl_parse_sint!(i8);
-impl_parse_sint!(i16);
-impl_parse_sint!(i32);
-impl_parse_sint!(i64);
-impl_parse_sint!(isize);
+/// Splits the whole input on `delim` and returns the unescaped fields.
+///
+/// Like in [`quoted_string`], `escape` followed by `delim` or by another
+/// `escape` stands for that character, and an `escape` before any other
+/// character is kept as is.  Like [`str::split`], it returns a single empty
+/// field for empty input.  Never fails.
+///
+/// ```rust
+/// use komb::{Parser, string::escaped_split};
+///
+/// let p = escaped_split(':', '\\');
+///
+/// assert_eq!(Ok((vec!["a".to_owned(), "b:c".to_owned(), "d".to_owned()], "")), p.parse(r"a:b\:c:d"));
+/// assert_eq!(Ok((vec![r"\n".to_owned(), r"x\".to_owned()], "")), p.parse(r"\n:x\\"));
+/// assert_eq!(Ok((vec![String::new(), String::new()], "")), p.parse(":"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn escaped_split<'a>(
+	delim: char,
+	escape: char,
+) -> impl Parser<'a, &'a str, Vec<String>, Error<'a>> {
+	move |input: &'a str| {
+		let mut fields = Vec::new();
+		let mut field = String::new();
+		let mut chars = input.chars().peekable();
 
-macro_rules! impl_parse_float {
-	($type:ident) => {
-		#[doc=concat!("Parses a [`", stringify!($type), "`][prim@", stringify!($type), "].")]
-		///
-		/// This function uses Rust-style grammar.  See
-		/// [`from_str`][prim@f64#method.from_str] for the description.
-		///
-		/// ```rust
-		/// use komb::Parser;
-		#[doc=concat!("use komb::string::", stringify!($type), ";")]
-		///
-		#[doc=concat!("assert_eq!(Ok((3.14, \"\")), ", stringify!($type), ".parse(\"3.14\"));")]
-		#[doc=concat!("assert_eq!(Ok((-3.14, \"\")), ", stringify!($type), ".parse(\"-3.14\"));")]
-		#[doc=concat!("assert_eq!(Ok((2.5E10, \"\")), ", stringify!($type), ".parse(\"2.5E10\"));")]
-		#[doc=concat!("assert_eq!(Ok((2.5E-10, \"\")), ", stringify!($type), ".parse(\"2.5E-10\"));")]
-		#[doc=concat!("assert_eq!(Ok((5.0, \"\")), ", stringify!($type), ".parse(\"5.\"));")]
-		#[doc=concat!("assert_eq!(Ok((0.5, \"\")), ", stringify!($type), ".parse(\".5\"));")]
-		#[doc=concat!("assert_eq!(Ok((", stringify!($type), "::INFINITY, \"\")), ", stringify!($type), ".parse(\"iNf\"));")]
-		#[doc=concat!("assert_eq!(Ok((", stringify!($type), "::NEG_INFINITY, \"\")), ", stringify!($type), ".parse(\"-inF\"));")]
-		/// ```
-		pub fn $type(input: &str) -> PResult<&str, $type, Error> {
-			use crate::combinator::optional;
+		while let Some(ch) = chars.next() {
+			if ch == delim {
+				fields.push(core::mem::take(&mut field));
+				continue;
+			}
 
-			fn sign(input: &str) -> PResult<&str, (), Error> {
-				optional(choice(('+', '-', "")))
-					.value(())
-					.parse(input)
+			match chars.peek() {
+				Some(&next)
+					if ch == escape
+						&& (next == delim
+							|| next == escape) =>
+				{
+					chars.next();
+					field.push(next);
+				}
+				_ => field.push(ch),
 			}
-			let exp = (anycase("e"), sign, digits::<10>);
-			let number = (
-				choice((
-					(digits::<10>, '.', or0(digits::<10>))
-						.value(()),
-					(or0(digits::<10>), '.', digits::<10>)
-						.value(()),
-					digits::<10>.value(()),
-				)),
-				optional(exp),
-			);
-			let float = (
-				sign,
-				choice((
-					anycase("inf").value(()),
-					anycase("infinity").value(()),
-					anycase("nan").value(()),
-					number.value(()),
-				)),
-			);
-			let s = consume(float);
+		}
+		fields.push(field);
 
-			let (span, rest) = s.parse(input)?;
+		Ok((fields, &input[input.len()..]))
+	}
+}
 
-			use core::str::FromStr;
-			let out = $type::from_str(span).map_err(|error| {
-				Error::ParseFloat { error, span }
-			})?;
+// Templates
 
-			Ok((out, rest))
-		}
+/// Matches a variable reference, either `${NAME}` or `$NAME`, and returns the
+/// name.
+///
+/// The name must start with an alphabetic character or an underscore, which
+/// can be followed by alphanumeric characters and underscores.  Empty names
+/// and unclosed braces are rejected.
+///
+/// ```rust
+/// use komb::{Parser, string::var_ref};
+///
+/// assert_eq!(Ok(("HOME", "/bin")), var_ref.parse("${HOME}/bin"));
+/// assert_eq!(Ok(("PATH", ":/bin")), var_ref.parse("$PATH:/bin"));
+/// assert!(var_ref.parse("${}").is_err());
+/// assert!(var_ref.parse("${HOME").is_err());
+/// assert!(var_ref.parse("${").is_err());
+/// ```
+pub fn var_ref(input: &str) -> PResult<&str, &str, Error<'_>> {
+	let name = || {
+		identifier(
+			|c| c.is_alphabetic() || c == '_',
+			|c| c.is_alphanumeric() || c == '_',
+		)
 	};
+
+	choice((delimited("${", name(), "}"), "$".and_then(name())))
+		.parse(input)
 }
 
-impl_parse_float!(f32);
-impl_parse_float!(f64);
+/// A part of a template, see [`template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+	/// Literal text which is copied verbatim.
+	Literal(&'a str),
+	/// The name of a variable which should be substituted.
+	Var(&'a str),
+}
+
+/// Splits a template string into literal text and variable references.
+///
+/// Variables use the [`var_ref`] syntax.  An escaped `$$` is returned as a
+/// separate `$` literal, so adjacent literal segments aren't merged.  Parsing
+/// stops at a `$` which doesn't start a valid variable.
+///
+/// ```rust
+/// use komb::{Parser, string::{template, Segment}};
+///
+/// assert_eq!(
+///     Ok((
+///         vec![
+///             Segment::Literal("Hello "),
+///             Segment::Var("name"),
+///             Segment::Literal(", you owe "),
+///             Segment::Literal("$"),
+///             Segment::Var("debt"),
+///             Segment::Literal("!"),
+///         ],
+///         ""
+///     )),
+///     template.parse("Hello ${name}, you owe $$$debt!")
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn template(input: &str) -> PResult<&str, Vec<Segment<'_>>, Error<'_>> {
+	let segment = choice((
+		"$$".map_out(|s: &str| Segment::Literal(&s[1..])),
+		var_ref.map_out(Segment::Var),
+		none_of(&['$']).map_out(Segment::Literal),
+	));
+
+	many0(segment).parse(input)
+}
 
 #[cfg(test)]
 mod test {
 	use super::*;
+	#[cfg(feature = "alloc")]
+	use alloc::vec;
+	use core::num::IntErrorKind;
 
 	#[test]
 	fn playground() {
@@ -553,4 +2794,232 @@ mod test {
 			one_of(&['a', 'b']).parse("abcd").unwrap()
 		);
 	}
+
+	#[test]
+	fn longest_tag_order() {
+		let p = longest_tag("<", "<=");
+		assert_eq!(Ok(("<=", "")), p.parse("<="));
+
+		let p = longest_tag("<=", "<");
+		assert_eq!(Ok(("<=", "")), p.parse("<="));
+		assert_eq!(Ok(("<", "x")), p.parse("<x"));
+	}
+
+	#[test]
+	fn boolean_with_any_case() {
+		let p = boolean_with(
+			&["true", "yes", "on", "1"],
+			&["false", "no", "off", "0"],
+			true,
+		);
+
+		assert_eq!(Ok((true, "")), p.parse("YES"));
+		assert_eq!(Ok((false, "")), p.parse("off"));
+		assert_eq!(Ok((true, "")), p.parse("1"));
+		assert_eq!(Err(Error::unmatched("m")), p.parse("maybe"));
+		assert!(p.parse("none").is_err());
+
+		let p = boolean_with(&["yes"], &["no"], false);
+		assert!(p.parse("YES").is_err());
+	}
+
+	#[test]
+	fn char_table_matches_predicate() {
+		let predicate = |ch: char| ch.is_alphanumeric() || ch == '-';
+		let table = CharTable::new(predicate);
+
+		for ch in ('\0'..='\u{3000}').chain(['ж', '日', '🦀']) {
+			assert_eq!(predicate(ch), table.contains(ch), "{ch:?}");
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn edit_distance_basic() {
+		assert_eq!(0, edit_distance("", ""));
+		assert_eq!(3, edit_distance("", "abc"));
+		assert_eq!(3, edit_distance("kitten", "sitting"));
+		assert_eq!(1, edit_distance("löve", "love"));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn render_clamps_to_first_line() {
+		let source = "let x = ab\ncd;";
+		let error = Error::unmatched(&source[8..13]);
+
+		assert_eq!(
+			"1:9: Parser failed to match 'ab\ncd'\nlet x = ab\n        ^~",
+			error.render(source)
+		);
+	}
+
+	#[test]
+	fn radix_prefixes() {
+		assert_eq!(Ok((0xb1, "")), hex_u64.parse("0b1"));
+		assert!(oct_u64.parse("0x10").is_err());
+		assert!(bin_u64.parse("0o1").is_err());
+		assert!(hex_u64.parse("0o7").is_err());
+
+		assert_eq!(
+			Ok((i64::MIN, "")),
+			hex_i64.parse("-0x8000000000000000")
+		);
+		assert!(matches!(
+			hex_i64.parse("0x8000000000000000"),
+			Err(Error::ParseInt {
+				span: "0x8000000000000000",
+				..
+			})
+		));
+		assert_eq!(Ok((-1, "")), bin_i64.parse("-0b1"));
+		assert!(matches!(
+			hex_i64.parse("-0x8000000000000001"),
+			Err(Error::ParseInt { error, span: "-0x8000000000000001" })
+				if *error.kind() == IntErrorKind::NegOverflow
+		));
+	}
+
+	#[test]
+	fn take_while_m_n_bounds() {
+		let any = |max| take_while_m_n(0, max, |_| true);
+		assert_eq!(Ok(("", "abc")), any(0).parse("abc"));
+		assert_eq!(Ok(("äö", "ü")), any(2).parse("äöü"));
+
+		let digits = take_while_m_n(2, 4, |c| c.is_ascii_digit());
+		assert_eq!(Ok(("1234", "5")), digits.parse("12345"));
+		assert_eq!(
+			Err(Error::Unmatched { span: "x" }),
+			digits.parse("1x")
+		);
+		assert_eq!(Err(Error::End { span: "" }), digits.parse("1"));
+	}
+
+	#[test]
+	#[should_panic]
+	fn take_while_m_n_inverted_bounds() {
+		let _ = take_while_m_n(2, 1, |_| true);
+	}
+
+	#[test]
+	fn scientific_exponent_overflow() {
+		assert!(matches!(
+			scientific("1e99999999999"),
+			Err(Error::ParseInt {
+				span: "99999999999",
+				..
+			})
+		));
+		assert_eq!(Ok(((1.0, 0), "em")), scientific("1em"));
+	}
+
+	#[test]
+	fn take_nothing() {
+		assert_eq!(Ok(("", "abc")), take(0).parse("abc"));
+		assert_eq!(Ok(("", "")), take(0).parse(""));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn context_keeps_cut() {
+		let error = Error::unmatched("x").cut().with_context("value");
+
+		assert!(error.is_cut());
+		assert_eq!(error, error.clone().cut());
+		assert_eq!(vec!["value"], error.context_stack());
+	}
+
+	#[test]
+	fn take_while_whole_input() {
+		assert_eq!(Ok(("abc", "")), take_while(|_| true).parse("abc"));
+		assert_eq!(
+			Ok(("", "abc")),
+			or0(take_while(|_| false)).parse("abc")
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn fixed_record_char_boundaries() {
+		let p = fixed_record(&[1, 2]);
+
+		assert_eq!(Ok((vec!["ä", "bc"], "d")), p.parse("äbcd"));
+		assert_eq!(Err(Error::End { span: "" }), p.parse("äb"));
+	}
+
+	#[test]
+	fn bit_widths() {
+		let ones = "1".repeat(64);
+
+		assert_eq!(Ok((-1, "")), twos_complement(64).parse(&ones));
+		assert_eq!(
+			Ok((-i64::MAX, "")),
+			sign_magnitude(64).parse(&ones)
+		);
+		assert_eq!(Ok((-1, "")), twos_complement(1).parse("1"));
+		assert_eq!(Ok((0, "")), sign_magnitude(1).parse("1"));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn fuzzy_tag_typo() {
+		let p = fuzzy_tag("select", 1);
+
+		assert_eq!(Ok(("selct", "")), p.parse("selct"));
+		assert_eq!(Ok(("select", " x")), p.parse("select x"));
+		assert_eq!(Err(Error::End { span: "" }), p.parse("sel"));
+		assert_eq!(
+			Err(Error::Unmatched { span: "update " }),
+			p.parse("update x")
+		);
+	}
+
+	#[test]
+	fn shebang() {
+		assert_eq!(
+			Ok(((), "x = 1")),
+			skip_shebang("#!/bin/sh\r\nx = 1")
+		);
+		assert_eq!(Ok(((), "")), skip_shebang("#!/bin/sh"));
+		assert_eq!(Ok(((), "# x\n")), skip_shebang("# x\n"));
+		assert_eq!(Ok(((), " #!/bin/sh")), skip_shebang(" #!/bin/sh"));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn shell_words() {
+		let p = shell_word();
+
+		assert_eq!(Ok(("a b".to_string(), " c")), p.parse("'a b' c"));
+		assert_eq!(Ok((r"a\b".to_string(), "")), p.parse(r"'a\b'"));
+		assert_eq!(Ok(("x\"y".to_string(), "")), p.parse(r#""x\"y""#));
+		assert_eq!(Ok((r"\n\".to_string(), "")), p.parse(r#""\n\\""#));
+		assert_eq!(
+			Ok(("abcd".to_string(), " e")),
+			p.parse(r#"a"b"c'd' e"#)
+		);
+		assert_eq!(Ok((String::new(), "")), p.parse("''"));
+		assert_eq!(Err(Error::End { span: "" }), p.parse(r#""x\""#));
+		assert_eq!(Err(Error::Unmatched { span: " " }), p.parse(" a"));
+	}
+
+	#[test]
+	#[cfg(feature = "alloc")]
+	fn shell_command_line() {
+		assert_eq!(
+			Ok((
+				vec![
+					"echo".into(),
+					"hello world".into(),
+					"a b".into()
+				],
+				""
+			)),
+			shell_split(r#"echo 'hello world' "a b""#)
+		);
+		assert_eq!(
+			Err(Error::End { span: "" }),
+			shell_split("echo 'hello world")
+		);
+	}
 }